@@ -0,0 +1,106 @@
+use crate::{game::Game, grid::BoolGrid2D};
+
+/// The longest period `classify` recognizes an oscillator by. Two
+/// generations would only catch blinkers and other period 2 ash; 15 also
+/// covers pulsars and pentadecathlons.
+const MAX_PERIOD: usize = 15;
+
+/// What a live cell's cluster does over the coming generations.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CellMotion {
+    /// Part of a still life.
+    Still,
+    /// Part of an oscillator that stays in place.
+    Oscillating,
+    /// Part of anything else, such as a spaceship or an active reaction.
+    Moving,
+}
+
+/// The live cells' bounding box of each cluster in `ids`, grown by a cell
+/// on every side and clipped to the board, as `(left, top, right, bottom)`.
+fn cluster_areas(grid: &BoolGrid2D, ids: &[Option<usize>]) -> Vec<(usize, usize, usize, usize)> {
+    let mut areas: Vec<(usize, usize, usize, usize)> = Vec::new();
+
+    for (index, id) in ids.iter().enumerate() {
+        let Some(id) = *id else {
+            continue;
+        };
+        let (x, y) = (index % grid.width, index / grid.width);
+        let around = (
+            x.saturating_sub(1),
+            y.saturating_sub(1),
+            (x + 1).min(grid.width - 1),
+            (y + 1).min(grid.height - 1),
+        );
+        if id == areas.len() {
+            areas.push(around);
+        } else {
+            let area = &mut areas[id];
+            *area = (
+                area.0.min(around.0),
+                area.1.min(around.1),
+                area.2.max(around.2),
+                area.3.max(around.3),
+            );
+        }
+    }
+
+    areas
+}
+
+/// Sorts every live cell by what its 8-connected cluster does, looking
+/// ahead without touching the board. A cluster is still when the area
+/// around it is the same next generation, oscillating when it comes back
+/// within `MAX_PERIOD` generations, and moving otherwise. Dead cells are
+/// `None`, and the result is indexed like `game.grid`.
+pub fn classify(game: &Game) -> Vec<Option<CellMotion>> {
+    let start = &game.grid;
+    let mut future = Vec::with_capacity(MAX_PERIOD);
+    let mut grid = start.clone();
+    for _ in 0..MAX_PERIOD {
+        grid = game.successor(&grid);
+        future.push(grid.clone());
+    }
+
+    let ids = start.component_ids();
+    let motions: Vec<CellMotion> = cluster_areas(start, &ids)
+        .into_iter()
+        .map(|(left, top, right, bottom)| {
+            let unchanged_in = |other: &BoolGrid2D| {
+                (top..=bottom).all(|y| (left..=right).all(|x| start.get(x, y) == other.get(x, y)))
+            };
+            if unchanged_in(&future[0]) {
+                CellMotion::Still
+            } else if future[1..].iter().any(unchanged_in) {
+                CellMotion::Oscillating
+            } else {
+                CellMotion::Moving
+            }
+        })
+        .collect();
+
+    ids.into_iter().map(|id| id.map(|id| motions[id])).collect()
+}
+
+/// How many live cells are still, oscillating and moving, in that order.
+pub fn count(motions: &[Option<CellMotion>]) -> (usize, usize, usize) {
+    let of = |kind| {
+        motions
+            .iter()
+            .filter(|&&motion| motion == Some(kind))
+            .count()
+    };
+    (
+        of(CellMotion::Still),
+        of(CellMotion::Oscillating),
+        of(CellMotion::Moving),
+    )
+}
+
+pub fn print_motion(game: &Game) {
+    let (still, oscillating, moving) = count(&classify(game));
+    println!("Motion:");
+    println!("  still       {}", still);
+    println!("  oscillating {}", oscillating);
+    println!("  moving      {}", moving);
+}
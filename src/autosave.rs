@@ -0,0 +1,98 @@
+use std::{
+    env, fs,
+    io::{self, BufRead, IsTerminal, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use conways_gol::{
+    game::{Game, PlacementMode},
+    rle,
+};
+
+/// Where the board is autosaved, in RLE.
+fn path() -> PathBuf {
+    env::temp_dir().join("conways_gol-autosave.rle")
+}
+
+/// Periodically writes the board to a file so a crash loses at most one
+/// interval of work.
+pub struct Autosave {
+    interval: Duration,
+    last_saved: Instant,
+}
+
+impl Autosave {
+    /// An interval of zero disables autosaving.
+    pub fn new(interval: Duration) -> Autosave {
+        Autosave {
+            interval,
+            last_saved: Instant::now(),
+        }
+    }
+
+    /// Saves the board if an interval has passed since the last save.
+    pub fn tick(&mut self, game: &Game) {
+        if self.interval.is_zero() || self.last_saved.elapsed() < self.interval {
+            return;
+        }
+        self.last_saved = Instant::now();
+
+        if let Err(error) = save(game, &path()) {
+            eprintln!("Couldn't autosave to {}: {}", path().display(), error);
+        }
+    }
+}
+
+/// Writes to a temporary file first and renames it into place, so a crash
+/// halfway through leaves the previous save intact.
+fn save(game: &Game, path: &Path) -> io::Result<()> {
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+
+    fs::write(&temporary, rle::to_rle(&game.grid, game.rule))?;
+    fs::rename(&temporary, path)
+}
+
+/// Saves the board to `path` as the window closes, reporting how it went
+/// on stderr.
+pub fn save_on_exit(game: &Game, path: &str) {
+    match save(game, Path::new(path)) {
+        Ok(()) => eprintln!("Saved the board to {}", path),
+        Err(error) => eprintln!("Couldn't save the board to {}: {}", path, error),
+    }
+}
+
+/// If an earlier session left an autosave behind, asks on the terminal
+/// whether to load it onto the board.
+pub fn offer_restore(game: &mut Game) {
+    let path = path();
+    let Ok(text) = fs::read_to_string(&path) else {
+        return;
+    };
+    if !io::stdin().is_terminal() {
+        return;
+    }
+
+    print!(
+        "Found an autosave at {}. Restore it? [y/N] ",
+        path.display()
+    );
+    // Without the prompt on screen there's nothing to answer.
+    if io::stdout().flush().is_err() {
+        return;
+    }
+
+    let mut answer = String::new();
+    if io::stdin().lock().read_line(&mut answer).is_err()
+        || !answer.trim().eq_ignore_ascii_case("y")
+    {
+        return;
+    }
+
+    match rle::from_rle(&text) {
+        // Autosaves are the whole board, so they go back where they were.
+        Ok(pattern) => game.load_pattern(&pattern, None, PlacementMode::TopLeft),
+        Err(error) => eprintln!("Couldn't read the autosave: {}", error),
+    }
+}
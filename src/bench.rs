@@ -0,0 +1,28 @@
+use std::time::{Duration, Instant};
+
+use conways_gol::game::Game;
+
+/// Runs `game` flat out for `duration` and prints how fast it went, in
+/// generations and cells per second.
+pub fn run(game: &mut Game, duration: Duration) {
+    let cells = game.grid.width() * game.grid.height();
+    let start = Instant::now();
+    let mut generations: u64 = 0;
+
+    while start.elapsed() < duration {
+        game.update();
+        generations += 1;
+    }
+
+    let seconds = start.elapsed().as_secs_f64();
+    let per_second = generations as f64 / seconds;
+    println!(
+        "Ran {} generations of a {}x{} board in {:.2}s",
+        generations,
+        game.grid.width(),
+        game.grid.height(),
+        seconds
+    );
+    println!("{:.1} generations/s", per_second);
+    println!("{:.0} cells/s", per_second * cells as f64);
+}
@@ -0,0 +1,625 @@
+use sfml::window::Key;
+
+use crate::grid::Axis;
+
+/// Everything the keyboard can ask the game to do.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    TogglePause,
+    /// Turns the simulation off completely, or back on.
+    ToggleSandbox,
+    ToggleHelp,
+    ToggleTrail,
+    /// Shades the background by how often each cell has changed lately.
+    ToggleActivity,
+    ToggleBirths,
+    ToggleComponents,
+    ToggleInspector,
+    /// Switches the focused cell's highlight between the cells that influence
+    /// it and the cells it influences.
+    ToggleInfluenced,
+    CycleBoundary,
+    /// Switches between synchronous and in-place scan order updates.
+    ToggleUpdateMode,
+    /// Draws row 0 at the bottom of the board, or back at the top.
+    ToggleYUp,
+    /// Moves the whole board by the given number of cells.
+    ShiftBoard(isize, isize),
+    Census,
+    /// Counts live cells that are still, oscillating or moving.
+    ReportMotion,
+    /// Kills still lifes and oscillators, keeping only moving objects.
+    ClearAsh,
+    ClearSelection,
+    /// Flips every cell the view shows.
+    InvertVisible,
+    /// Kills every cell the view shows.
+    ClearVisible,
+    Deselect,
+    /// Confines the simulation to the selection, or lifts that limit.
+    ToggleActiveRegion,
+    /// Marks the selection as cells live cells mustn't enter, or unmarks it.
+    ToggleForbidden,
+    /// Widens or narrows the gap between drawn cells by this many steps.
+    AdjustPadding(i32),
+    /// Raises or lowers the noise rate by this many steps.
+    AdjustNoise(i32),
+    PasteRle,
+    /// Saves the live cells, cropped to their bounding box, as RLE.
+    SaveCrop,
+    /// Saves the live cells' coordinates as CSV.
+    SaveCsv,
+    /// Repeats the live cells' bounding box across the board.
+    TileContent,
+    /// Starts or stops logging the hovered cell's state every generation.
+    ToggleProbe,
+    /// Appends the probe readings taken so far to a log file.
+    FlushProbes,
+    /// Turns the pattern waiting to be placed by a quarter turn.
+    RotatePattern,
+    /// Adds the board's mirror image along an axis to itself.
+    OverlayMirrored(Axis),
+    /// Adds a margin of dead cells on every side of the board.
+    Grow,
+    /// Crops the same margin off every side.
+    Shrink,
+    /// Keeps the view on the live cells' center of mass.
+    ToggleFollow,
+    /// Shows how long updating and drawing take.
+    ToggleTimings,
+    /// Shows or hides the rule, boundary and speed.
+    ToggleStatus,
+    /// Runs one generation per frame with no frame rate limit.
+    ToggleMaxSpeed,
+    TogglePopulationGraph,
+    /// Steps the symmetry brush through off, left-right, top-bottom and
+    /// 4-fold.
+    CycleSymmetry,
+    /// Outlines the largest empty rectangle, or hides the outline.
+    ToggleQuietZone,
+    /// Outlines the live cells' bounding box, or stops.
+    ToggleBoundingBox,
+    /// Shows or hides cell indices along the window's edges.
+    ToggleRulers,
+    /// Shows or hides coordinates inside cells that are zoomed in enough.
+    ToggleCellLabels,
+    /// Restarts the random number sequence from the session's seed.
+    ResetRng,
+    /// Undoes every rule mutation so far.
+    RevertMutations,
+    /// Goes back to the board simulation last started from.
+    Rewind,
+    /// Shows or hides the panel of clickable rule checkboxes.
+    ToggleRuleEditor,
+    /// Switches to the rule named in the last pasted pattern's header.
+    ApplyOfferedRule,
+    /// Highlights the cells that differ from the reference board.
+    ToggleDifferences,
+    /// Makes the current board the reference.
+    SetReference,
+    /// Saves the board into a numbered checkpoint slot.
+    SaveCheckpoint(u8),
+    RestoreCheckpoint(u8),
+}
+
+pub struct KeyBinding {
+    pub key: Key,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub label: &'static str,
+    pub action: Action,
+    pub description: &'static str,
+}
+
+impl KeyBinding {
+    const fn new(
+        key: Key,
+        label: &'static str,
+        action: Action,
+        description: &'static str,
+    ) -> KeyBinding {
+        KeyBinding {
+            key,
+            ctrl: false,
+            shift: false,
+            label,
+            action,
+            description,
+        }
+    }
+
+    const fn with_ctrl(self) -> KeyBinding {
+        KeyBinding { ctrl: true, ..self }
+    }
+
+    const fn with_shift(self) -> KeyBinding {
+        KeyBinding {
+            shift: true,
+            ..self
+        }
+    }
+
+    fn matches(&self, code: Key, ctrl: bool, shift: bool) -> bool {
+        self.key == code && self.ctrl == ctrl && self.shift == shift
+    }
+}
+
+/// The single source of truth for keyboard controls. Both event handling and
+/// the help overlay read from this table, so adding a binding here is enough
+/// for it to show up in the help.
+pub const KEY_BINDINGS: &[KeyBinding] = &[
+    KeyBinding::new(Key::SPACE, "Space", Action::TogglePause, "Pauses the game"),
+    KeyBinding::new(
+        Key::SPACE,
+        "Space",
+        Action::ToggleSandbox,
+        "Enters or leaves sandbox mode, where the game never runs",
+    )
+    .with_ctrl(),
+    KeyBinding::new(
+        Key::H,
+        "H",
+        Action::ToggleHelp,
+        "Shows this help, then its next page, then hides it",
+    ),
+    KeyBinding::new(
+        Key::T,
+        "T",
+        Action::ToggleTrail,
+        "Toggles fading cell trails",
+    ),
+    KeyBinding::new(
+        Key::T,
+        "T",
+        Action::ToggleActivity,
+        "Toggles a heatmap of where cells have changed lately",
+    )
+    .with_shift(),
+    KeyBinding::new(
+        Key::F,
+        "F",
+        Action::ToggleBirths,
+        "Flashes newly born cells in an accent color",
+    ),
+    KeyBinding::new(
+        Key::K,
+        "K",
+        Action::ToggleComponents,
+        "Colors each connected cluster differently",
+    ),
+    KeyBinding::new(
+        Key::I,
+        "I",
+        Action::ToggleInspector,
+        "Explains the hovered cell's next state",
+    ),
+    KeyBinding::new(
+        Key::I,
+        "I",
+        Action::ToggleInfluenced,
+        "Highlights what the focused cell influences instead of what influences it",
+    )
+    .with_shift(),
+    KeyBinding::new(
+        Key::B,
+        "B",
+        Action::CycleBoundary,
+        "Cycles edges: dead, torus, Klein bottle, projective plane",
+    ),
+    KeyBinding::new(
+        Key::B,
+        "B",
+        Action::ToggleBoundingBox,
+        "Outlines the rectangle holding every live cell",
+    )
+    .with_shift(),
+    KeyBinding::new(
+        Key::U,
+        "U",
+        Action::ToggleUpdateMode,
+        "Switches between updating all cells at once and one at a time",
+    ),
+    KeyBinding::new(
+        Key::Y,
+        "Y",
+        Action::ToggleYUp,
+        "Flips the board so row 0 is at the bottom, or back",
+    ),
+    KeyBinding::new(
+        Key::UP,
+        "Up",
+        Action::ShiftBoard(0, -1),
+        "Moves the board up",
+    )
+    .with_shift(),
+    KeyBinding::new(
+        Key::DOWN,
+        "Down",
+        Action::ShiftBoard(0, 1),
+        "Moves the board down",
+    )
+    .with_shift(),
+    KeyBinding::new(
+        Key::LEFT,
+        "Left",
+        Action::ShiftBoard(-1, 0),
+        "Moves the board left",
+    )
+    .with_shift(),
+    KeyBinding::new(
+        Key::RIGHT,
+        "Right",
+        Action::ShiftBoard(1, 0),
+        "Moves the board right",
+    )
+    .with_shift(),
+    KeyBinding::new(
+        Key::C,
+        "C",
+        Action::Census,
+        "Counts still lifes, blinkers and gliders",
+    ),
+    KeyBinding::new(
+        Key::N,
+        "N",
+        Action::AdjustNoise(1),
+        "Raises the rate of random births",
+    ),
+    KeyBinding::new(
+        Key::N,
+        "N",
+        Action::AdjustNoise(-1),
+        "Lowers the rate of random births",
+    )
+    .with_shift(),
+    KeyBinding::new(
+        Key::W,
+        "W",
+        Action::ReportMotion,
+        "Counts live cells that are still, oscillating or moving",
+    ),
+    KeyBinding::new(
+        Key::W,
+        "W",
+        Action::ClearAsh,
+        "Clears still lifes and oscillators, leaving only what moves",
+    )
+    .with_shift(),
+    KeyBinding::new(
+        Key::DELETE,
+        "Delete",
+        Action::ClearSelection,
+        "Clears the selected region",
+    ),
+    KeyBinding::new(
+        Key::DELETE,
+        "Delete",
+        Action::ClearVisible,
+        "Clears every cell in view",
+    )
+    .with_ctrl(),
+    KeyBinding::new(
+        Key::I,
+        "I",
+        Action::InvertVisible,
+        "Inverts every cell in view",
+    )
+    .with_ctrl(),
+    KeyBinding::new(
+        Key::ESCAPE,
+        "Escape",
+        Action::Deselect,
+        "Drops the selection or the pattern being placed",
+    ),
+    KeyBinding::new(
+        Key::A,
+        "A",
+        Action::ToggleActiveRegion,
+        "Simulates only the selected region, or the whole board again",
+    ),
+    KeyBinding::new(
+        Key::RBRACKET,
+        "]",
+        Action::AdjustPadding(1),
+        "Widens the gap between cells",
+    ),
+    KeyBinding::new(
+        Key::LBRACKET,
+        "[",
+        Action::AdjustPadding(-1),
+        "Narrows the gap between cells",
+    ),
+    KeyBinding::new(
+        Key::Q,
+        "Q",
+        Action::ToggleProbe,
+        "Starts or stops logging the hovered cell every generation",
+    ),
+    KeyBinding::new(
+        Key::Q,
+        "Q",
+        Action::FlushProbes,
+        "Appends the probe log to probes.log",
+    )
+    .with_shift(),
+    KeyBinding::new(
+        Key::Z,
+        "Z",
+        Action::ToggleForbidden,
+        "Marks the selection forbidden to live cells, or allows it again",
+    ),
+    KeyBinding::new(
+        Key::Z,
+        "Z",
+        Action::ToggleQuietZone,
+        "Outlines the largest empty rectangle, for placing new patterns",
+    )
+    .with_shift(),
+    KeyBinding::new(
+        Key::V,
+        "V",
+        Action::PasteRle,
+        "Picks up an RLE pattern from the clipboard to place with a click",
+    )
+    .with_ctrl()
+    .with_shift(),
+    KeyBinding::new(
+        Key::S,
+        "S",
+        Action::SaveCrop,
+        "Saves the live cells, cropped, to an RLE file",
+    )
+    .with_ctrl(),
+    KeyBinding::new(
+        Key::S,
+        "S",
+        Action::SaveCsv,
+        "Saves the live cells' coordinates to a CSV file",
+    )
+    .with_ctrl()
+    .with_shift(),
+    KeyBinding::new(
+        Key::T,
+        "T",
+        Action::TileContent,
+        "Repeats the live cells across the board like wallpaper",
+    )
+    .with_ctrl(),
+    KeyBinding::new(
+        Key::O,
+        "O",
+        Action::RotatePattern,
+        "Rotates the pattern being placed clockwise",
+    ),
+    KeyBinding::new(
+        Key::M,
+        "M",
+        Action::OverlayMirrored(Axis::Horizontal),
+        "Mirrors the board left to right onto itself",
+    ),
+    KeyBinding::new(
+        Key::M,
+        "M",
+        Action::OverlayMirrored(Axis::Vertical),
+        "Mirrors the board top to bottom onto itself",
+    )
+    .with_shift(),
+    KeyBinding::new(
+        Key::M,
+        "M",
+        Action::CycleSymmetry,
+        "Cycles the symmetry brush: off, left-right, top-bottom, 4-fold",
+    )
+    .with_ctrl(),
+    KeyBinding::new(
+        Key::G,
+        "G",
+        Action::Grow,
+        "Grows the board by 10 cells on every side",
+    ),
+    KeyBinding::new(
+        Key::G,
+        "G",
+        Action::Shrink,
+        "Shrinks the board by 10 cells on every side, cropping what's there",
+    )
+    .with_shift(),
+    KeyBinding::new(
+        Key::P,
+        "P",
+        Action::ToggleFollow,
+        "Pans the view to follow the live cells",
+    ),
+    KeyBinding::new(
+        Key::F3,
+        "F3",
+        Action::ToggleTimings,
+        "Shows how long updating and drawing take",
+    ),
+    KeyBinding::new(
+        Key::L,
+        "L",
+        Action::TogglePopulationGraph,
+        "Graphs the population over recent generations",
+    ),
+    KeyBinding::new(
+        Key::E,
+        "E",
+        Action::ToggleRulers,
+        "Shows or hides cell index rulers along the window edges",
+    ),
+    KeyBinding::new(
+        Key::E,
+        "E",
+        Action::ToggleCellLabels,
+        "Shows or hides coordinates inside cells when zoomed in",
+    )
+    .with_shift(),
+    KeyBinding::new(
+        Key::X,
+        "X",
+        Action::ToggleMaxSpeed,
+        "Runs a generation every frame, as fast as possible",
+    ),
+    KeyBinding::new(
+        Key::S,
+        "S",
+        Action::ToggleStatus,
+        "Shows or hides the rule, boundary and speed",
+    ),
+    KeyBinding::new(
+        Key::R,
+        "R",
+        Action::ResetRng,
+        "Restarts random numbers from the seed shown above",
+    ),
+    KeyBinding::new(
+        Key::R,
+        "R",
+        Action::ApplyOfferedRule,
+        "Switches to the rule the pasted pattern was made for",
+    )
+    .with_ctrl(),
+    KeyBinding::new(
+        Key::R,
+        "R",
+        Action::ToggleRuleEditor,
+        "Shows checkboxes for editing the rule with the mouse",
+    )
+    .with_shift(),
+    KeyBinding::new(
+        Key::J,
+        "J",
+        Action::RevertMutations,
+        "Goes back to the rule from before --mutate-rule changed it",
+    ),
+    KeyBinding::new(
+        Key::HOME,
+        "Home",
+        Action::Rewind,
+        "Rewinds to the board the simulation started from, at generation 0",
+    ),
+    KeyBinding::new(
+        Key::D,
+        "D",
+        Action::ToggleDifferences,
+        "Highlights and counts cells that differ from the reference",
+    ),
+    KeyBinding::new(
+        Key::D,
+        "D",
+        Action::SetReference,
+        "Makes the current board the reference",
+    )
+    .with_shift(),
+    KeyBinding::new(
+        Key::NUM1,
+        "1",
+        Action::SaveCheckpoint(1),
+        "Saves the board as checkpoint 1",
+    )
+    .with_ctrl(),
+    KeyBinding::new(
+        Key::NUM1,
+        "1",
+        Action::RestoreCheckpoint(1),
+        "Restores checkpoint 1",
+    )
+    .with_shift(),
+    KeyBinding::new(
+        Key::NUM2,
+        "2",
+        Action::SaveCheckpoint(2),
+        "Saves the board as checkpoint 2",
+    )
+    .with_ctrl(),
+    KeyBinding::new(
+        Key::NUM2,
+        "2",
+        Action::RestoreCheckpoint(2),
+        "Restores checkpoint 2",
+    )
+    .with_shift(),
+    KeyBinding::new(
+        Key::NUM3,
+        "3",
+        Action::SaveCheckpoint(3),
+        "Saves the board as checkpoint 3",
+    )
+    .with_ctrl(),
+    KeyBinding::new(
+        Key::NUM3,
+        "3",
+        Action::RestoreCheckpoint(3),
+        "Restores checkpoint 3",
+    )
+    .with_shift(),
+];
+
+pub const MOUSE_BINDINGS: &[(&str, &str)] = &[
+    ("Mouse click", "Toggles cell state"),
+    ("Mouse drag", "Paints live cells"),
+    ("Right drag", "Erases cells"),
+    ("Ctrl+Mouse click", "Locks or unlocks a cell"),
+    ("Shift+Mouse click", "Highlights the neighbors of a cell"),
+    ("Alt+Mouse drag", "Draws a straight line of cells"),
+    ("Shift+Right drag", "Selects a region"),
+    ("Mouse wheel", "Zooms around the pointer"),
+];
+
+pub fn ctrl_held() -> bool {
+    Key::LCONTROL.is_pressed() || Key::RCONTROL.is_pressed()
+}
+
+pub fn alt_held() -> bool {
+    Key::LALT.is_pressed() || Key::RALT.is_pressed()
+}
+
+pub fn shift_held() -> bool {
+    Key::LSHIFT.is_pressed() || Key::RSHIFT.is_pressed()
+}
+
+pub fn find_action(code: Key, ctrl: bool, shift: bool) -> Option<Action> {
+    KEY_BINDINGS
+        .iter()
+        .find(|binding| binding.matches(code, ctrl, shift))
+        .map(|binding| binding.action)
+}
+
+/// One aligned `control  description` line per binding, keys first.
+pub fn help_lines() -> Vec<String> {
+    let keys = KEY_BINDINGS.iter().map(|binding| {
+        let mut label = String::new();
+        if binding.ctrl {
+            label.push_str("Ctrl+");
+        }
+        if binding.shift {
+            label.push_str("Shift+");
+        }
+        label.push_str(binding.label);
+
+        (label, binding.description)
+    });
+    let mouse = MOUSE_BINDINGS
+        .iter()
+        .map(|(label, description)| (label.to_string(), *description));
+    let entries: Vec<_> = keys.chain(mouse).collect();
+
+    let width = entries
+        .iter()
+        .map(|(label, _)| label.len())
+        .max()
+        .unwrap_or(0);
+
+    entries
+        .iter()
+        .map(|(label, description)| format!("{:width$}  {}", label, description, width = width))
+        .collect()
+}
+
+pub fn print_help(seed: u64) {
+    println!("Controls (seed {}):", seed);
+    for line in help_lines() {
+        println!("  {}", line);
+    }
+}
@@ -0,0 +1,68 @@
+use std::{
+    fs::File,
+    io::{Seek, SeekFrom, Write},
+};
+
+use conways_gol::{framebuffer::Framebuffer, game::Game};
+use sfml::graphics::Color;
+
+/// Keeps the latest frame, as raw RGBA pixels, at the start of a file of
+/// fixed size, so capture tools can memory-map it or read it at their own
+/// pace.
+pub struct Capture {
+    framebuffer: Framebuffer,
+    file: File,
+    path: String,
+    /// Set once a write fails, so the error is only reported once.
+    failed: bool,
+}
+
+impl Capture {
+    /// Sizes the frames to the board, at its cell size in pixels.
+    pub fn open(path: &str, game: &Game) -> Result<Capture, String> {
+        let world = game.world_size();
+        let (width, height) = (world.x.round().max(1.0), world.y.round().max(1.0));
+        let framebuffer = Framebuffer::new(width as u32, height as u32)
+            .ok_or_else(|| format!("couldn't make a {}x{} framebuffer", width, height))?;
+        let file =
+            File::create(path).map_err(|error| format!("couldn't create {}: {}", path, error))?;
+
+        Ok(Capture {
+            framebuffer,
+            file,
+            path: path.to_string(),
+            failed: false,
+        })
+    }
+
+    /// Draws `game` into the framebuffer and writes its pixels over the
+    /// previous frame's.
+    pub fn frame(&mut self, game: &Game) {
+        if self.failed {
+            return;
+        }
+        let Some(image) = self.framebuffer.render(game, Color::rgb(0, 0, 0)) else {
+            return;
+        };
+
+        let written = self
+            .file
+            .seek(SeekFrom::Start(0))
+            .and_then(|_| self.file.write_all(image.pixel_data()));
+        if let Err(error) = written {
+            eprintln!("Couldn't write a frame to {}: {}", self.path, error);
+            self.failed = true;
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        let size = self.framebuffer.size();
+        format!(
+            "Writing {}x{} RGBA frames to {}, {} bytes each",
+            size.x,
+            size.y,
+            self.path,
+            size.x as usize * size.y as usize * 4
+        )
+    }
+}
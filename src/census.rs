@@ -0,0 +1,336 @@
+use std::collections::HashSet;
+
+use crate::grid::BoolGrid2D;
+
+type Cells = Vec<(i32, i32)>;
+type Transform = fn(i32, i32) -> (i32, i32);
+
+/// How far in from the board's edge `escaping_gliders` looks. A glider
+/// touching the edge lies entirely within this many cells of it.
+const EDGE_BAND: usize = 2;
+
+/// The 4 rotations, then the same rotations mirrored.
+const SYMMETRIES: [Transform; 8] = [
+    |x, y| (x, y),
+    |x, y| (-y, x),
+    |x, y| (-x, -y),
+    |x, y| (y, -x),
+    |x, y| (-x, y),
+    |x, y| (y, x),
+    |x, y| (x, -y),
+    |x, y| (-y, -x),
+];
+
+struct CatalogEntry {
+    name: &'static str,
+    /// Every phase of the object, drawn with `O` for live cells.
+    phases: &'static [&'static [&'static str]],
+}
+
+const CATALOG: &[CatalogEntry] = &[
+    CatalogEntry {
+        name: "block",
+        phases: &[&["OO", "OO"]],
+    },
+    CatalogEntry {
+        name: "beehive",
+        phases: &[&[".OO.", "O..O", ".OO."]],
+    },
+    CatalogEntry {
+        name: "loaf",
+        phases: &[&[".OO.", "O..O", ".O.O", "..O."]],
+    },
+    CatalogEntry {
+        name: "boat",
+        phases: &[&["OO.", "O.O", ".O."]],
+    },
+    CatalogEntry {
+        name: "tub",
+        phases: &[&[".O.", "O.O", ".O."]],
+    },
+    CatalogEntry {
+        name: "blinker",
+        phases: &[&["OOO"]],
+    },
+    CatalogEntry {
+        name: "glider",
+        phases: &[
+            &[".O.", "..O", "OOO"],
+            &["O.O", ".OO", ".O."],
+            &["..O", "O.O", ".OO"],
+            &["O..", ".OO", "OO."],
+        ],
+    },
+];
+
+fn parse_phase(rows: &[&str]) -> Cells {
+    rows.iter()
+        .enumerate()
+        .flat_map(|(y, row)| {
+            row.chars()
+                .enumerate()
+                .filter(|(_, c)| *c == 'O')
+                .map(move |(x, _)| (x as i32, y as i32))
+        })
+        .collect()
+}
+
+/// Shifts `cells` so their bounding box starts at the origin, then sorts them.
+fn normalize(mut cells: Cells) -> Cells {
+    let min_x = cells.iter().map(|&(x, _)| x).min().unwrap_or(0);
+    let min_y = cells.iter().map(|&(_, y)| y).min().unwrap_or(0);
+
+    for cell in &mut cells {
+        cell.0 -= min_x;
+        cell.1 -= min_y;
+    }
+    cells.sort_unstable();
+    cells
+}
+
+/// The smallest of the 8 rotations and reflections of `cells`, so that two
+/// shapes are congruent exactly when their canonical forms are equal.
+fn canonical(cells: &[(i32, i32)]) -> Cells {
+    SYMMETRIES
+        .iter()
+        .map(|transform| normalize(cells.iter().map(|&(x, y)| transform(x, y)).collect()))
+        .min()
+        .unwrap_or_default()
+}
+
+/// Groups live cells into 8-connected clusters.
+fn clusters(grid: &BoolGrid2D) -> Vec<Cells> {
+    let mut clusters: Vec<Cells> = Vec::new();
+
+    for (index, id) in grid.component_ids().into_iter().enumerate() {
+        if let Some(id) = id {
+            if id == clusters.len() {
+                clusters.push(Vec::new());
+            }
+            let (x, y) = (index % grid.width, index / grid.width);
+            clusters[id].push((x as i32, y as i32));
+        }
+    }
+
+    clusters
+}
+
+/// Counts the catalogued objects on the board. Anything unrecognized is
+/// tallied under `"other"`, which is listed last.
+pub fn census(grid: &BoolGrid2D) -> Vec<(&'static str, usize)> {
+    let known: Vec<(usize, Cells)> = CATALOG
+        .iter()
+        .enumerate()
+        .flat_map(|(entry, catalog)| {
+            catalog
+                .phases
+                .iter()
+                .map(move |phase| (entry, canonical(&parse_phase(phase))))
+        })
+        .collect();
+
+    let mut counts = vec![0; CATALOG.len()];
+    let mut other = 0;
+
+    for cluster in clusters(grid) {
+        let form = canonical(&cluster);
+        match known.iter().find(|(_, known_form)| *known_form == form) {
+            Some((entry, _)) => counts[*entry] += 1,
+            None => other += 1,
+        }
+    }
+
+    CATALOG
+        .iter()
+        .zip(counts)
+        .map(|(entry, count)| (entry.name, count))
+        .chain(std::iter::once(("other", other)))
+        .collect()
+}
+
+/// Which way a glider shaped like `cells` travels, as a diagonal step
+/// such as `(1, 1)` for down and to the right, or `None` if `cells` aren't
+/// a glider.
+pub fn glider_heading(cells: &[(i32, i32)]) -> Option<(i32, i32)> {
+    let form = normalize(cells.to_vec());
+    let phases = CATALOG.iter().find(|entry| entry.name == "glider")?.phases;
+
+    // The catalog's phases all head down and to the right, so a glider
+    // heads wherever the symmetry that produces it takes that direction.
+    SYMMETRIES.iter().find_map(|transform| {
+        phases
+            .iter()
+            .any(|phase| {
+                let moved = parse_phase(phase)
+                    .into_iter()
+                    .map(|(x, y)| transform(x, y))
+                    .collect();
+                normalize(moved) == form
+            })
+            .then(|| transform(1, 1))
+    })
+}
+
+/// The gliders touching the edge of the board and heading off it, each as
+/// its live cells. Only cells within `EDGE_BAND` of the edge are looked at,
+/// and a glider counts only when no other live cell touches it.
+pub fn escaping_gliders(grid: &BoolGrid2D) -> Vec<Cells> {
+    let (width, height) = (grid.width, grid.height);
+    let near_edge = |x: usize, y: usize| {
+        x <= EDGE_BAND
+            || y <= EDGE_BAND
+            || x + EDGE_BAND + 1 >= width
+            || y + EDGE_BAND + 1 >= height
+    };
+    let neighbors = |x: usize, y: usize| {
+        (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+            .filter(|&offset| offset != (0, 0))
+            .filter_map(move |(dx, dy)| {
+                let nx = usize::try_from(x as isize + dx).ok()?;
+                let ny = usize::try_from(y as isize + dy).ok()?;
+                (nx < width && ny < height).then_some((nx, ny))
+            })
+    };
+
+    // The band's cells in row-major order: whole rows along the top and
+    // bottom, and a strip at each end of the rows in between.
+    let right_strip = (EDGE_BAND + 1).max(width.saturating_sub(EDGE_BAND + 1));
+    let band = (0..height).flat_map(|y| {
+        let columns: Vec<usize> = if y <= EDGE_BAND || y + EDGE_BAND + 1 >= height {
+            (0..width).collect()
+        } else {
+            (0..=EDGE_BAND.min(width - 1))
+                .chain(right_strip..width)
+                .collect()
+        };
+        columns.into_iter().map(move |x| (x, y))
+    });
+
+    let (right, bottom) = (width as i32 - 1, height as i32 - 1);
+    let mut seen = HashSet::new();
+    let mut gliders = Vec::new();
+
+    for (x, y) in band {
+        if !grid.get(x, y) || !seen.insert((x, y)) {
+            continue;
+        }
+
+        let mut cluster = vec![(x, y)];
+        let mut next = 0;
+        while next < cluster.len() {
+            let (cx, cy) = cluster[next];
+            next += 1;
+            for (nx, ny) in neighbors(cx, cy) {
+                if near_edge(nx, ny) && grid.get(nx, ny) && seen.insert((nx, ny)) {
+                    cluster.push((nx, ny));
+                }
+            }
+        }
+
+        // Anything live touching the cluster from outside the band means
+        // it's part of something bigger.
+        let isolated = cluster.iter().all(|&(cx, cy)| {
+            neighbors(cx, cy).all(|(nx, ny)| !grid.get(nx, ny) || cluster.contains(&(nx, ny)))
+        });
+        if !isolated || cluster.len() != 5 {
+            continue;
+        }
+
+        let cells: Cells = cluster
+            .into_iter()
+            .map(|(cx, cy)| (cx as i32, cy as i32))
+            .collect();
+        let Some((dx, dy)) = glider_heading(&cells) else {
+            continue;
+        };
+        let leaving = cells.iter().any(|&(cx, cy)| {
+            (cx == 0 && dx < 0)
+                || (cx == right && dx > 0)
+                || (cy == 0 && dy < 0)
+                || (cy == bottom && dy > 0)
+        });
+        if leaving {
+            gliders.push(cells);
+        }
+    }
+
+    gliders
+}
+
+pub fn print_census(grid: &BoolGrid2D) {
+    println!("Census:");
+    for (name, count) in census(grid) {
+        println!("  {:8} {}", name, count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packed::PackedGrid;
+
+    fn board(width: usize, height: usize, cells: &[(i32, i32)], x: i32, y: i32) -> BoolGrid2D {
+        let mut grid = BoolGrid2D::new(width, height);
+        for &(cx, cy) in cells {
+            grid.set((x + cx) as usize, (y + cy) as usize, true);
+        }
+        grid
+    }
+
+    fn glider(transform: Transform) -> Cells {
+        normalize(
+            parse_phase(&[".O.", "..O", "OOO"])
+                .into_iter()
+                .map(|(x, y)| transform(x, y))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn glider_heading_matches_how_gliders_move() {
+        for transform in SYMMETRIES {
+            let cells = glider(transform);
+            let heading = glider_heading(&cells).expect("a glider");
+
+            let mut grid = board(20, 20, &cells, 8, 8);
+            for _ in 0..4 {
+                let mut next = BoolGrid2D::new(20, 20);
+                PackedGrid::from_grid(&grid).step().write_to(&mut next);
+                grid = next;
+            }
+            let (moved, _) = grid.content_bounds().expect("the glider survives");
+            assert_eq!(
+                (moved.0 as i32 - 8, moved.1 as i32 - 8),
+                heading,
+                "glider {:?}",
+                cells
+            );
+        }
+    }
+
+    #[test]
+    fn glider_heading_rejects_other_shapes() {
+        assert_eq!(glider_heading(&parse_phase(&["OOO"])), None);
+        assert_eq!(glider_heading(&parse_phase(&["OO", "OO"])), None);
+    }
+
+    #[test]
+    fn escaping_gliders_finds_only_lone_gliders_heading_off() {
+        // Heads down and to the right, into the bottom-right corner.
+        let outward = glider(SYMMETRIES[0]);
+        assert_eq!(escaping_gliders(&board(20, 20, &outward, 17, 17)).len(), 1);
+
+        // The same glider at the top-left corner is heading back in.
+        assert!(escaping_gliders(&board(20, 20, &outward, 0, 0)).is_empty());
+
+        // Away from the edge it isn't escaping yet.
+        assert!(escaping_gliders(&board(20, 20, &outward, 8, 8)).is_empty());
+
+        // A live cell touching it from inside the board makes it something
+        // else.
+        let mut crowded = board(20, 20, &outward, 17, 17);
+        crowded.set(16, 18, true);
+        assert!(escaping_gliders(&crowded).is_empty());
+    }
+}
@@ -0,0 +1,785 @@
+use std::{str::FromStr, time::Duration};
+
+use sfml::{graphics::Color, system::Vector2f};
+
+use conways_gol::{
+    formula::Formula,
+    game::{PlacementMode, Symmetry, POPULATION_HISTORY_LENGTH},
+    grid::{Anchor, BoolOp, Boundary, GridTopology},
+    library::{self, LibraryPattern},
+    rule::Rule,
+};
+
+use crate::{headless::StopCondition, reveal::RevealOrder};
+
+const DEFAULT_CELL_SIZE: f32 = 10.0;
+const DEFAULT_TRAIL_DECAY: f32 = 0.1;
+const DEFAULT_ACTIVITY_DECAY: f32 = 0.95;
+const DEFAULT_MAX_GENERATIONS: u64 = 10_000;
+pub const DEFAULT_MAX_PERIOD: usize = 2;
+const DEFAULT_AUTOSAVE_INTERVAL: u64 = 60;
+const DEFAULT_GIF_GENERATIONS: u64 = 100;
+const DEFAULT_GIF_DELAY_MS: u64 = 100;
+const DEFAULT_BOARD_SIZE: usize = 40;
+const DEFAULT_BENCH_SECONDS: f64 = 10.0;
+const DEFAULT_BENCH_DENSITY: f32 = 0.5;
+const DEFAULT_EQUILIBRIUM_VARIANCE: f64 = 1.0;
+
+pub const USAGE: &str = "\
+Usage: conways_gol [OPTIONS]
+
+Options:
+  --width W            Board width in cells (default 40)
+  --height H           Board height in cells (default 40)
+  --resize-to W,H      Crops or extends the board to W by H once patterns are loaded, for trimming before export
+  --resize-anchor MODE  What --resize-to keeps in place: top-left (default) or center
+  --view-center X,Y    Initial view center in world units (default: middle of the board)
+  --view-size W,H      Initial view size in world units (default: the whole board)
+  --cell-width W       Width of each cell in world units (default 10)
+  --cell-height H      Height of each cell in world units (default 10)
+  --cell-padding P     Gap left between drawn cells in world units (default 0)
+  --tile-spacing N     Dead cells left between the copies Ctrl+T tiles (default 1)
+  --y-up               Draws row 0 at the bottom of the board instead of the top
+  --trail-decay RATE   How much cell trails fade per generation, in (0, 1] (default 0.1)
+  --activity-window N  How many generations a change counts towards the Shift+T heatmap (default 100)
+  --activity-decay RATE The fraction of its heat a heatmap change keeps each generation, in (0, 1] (default 0.95)
+  --start-paused       Starts paused, so a pattern can be drawn before it runs
+  --reveal SECONDS     Brings the starting cells in gradually over this long before running (default 0, off)
+  --reveal-order ORDER The order --reveal uses: scan or random (default scan)
+  --sandbox            Never runs the simulation, for drawing and exporting patterns
+  --ticks-per-frame N  Runs exactly N generations every frame, ignoring the tick rate and real time
+  --pause-at N         Pauses automatically once generation N is reached
+  --burn-in N          Runs N generations without drawing before the window opens
+  --pause-when-view-empty  Pauses once no live cells are left in view
+  --generation-cap N   Never runs more than N generations past the last time the board was cleared
+  --seed N             Seeds the random number generator for reproducible runs
+  --mutate-rule K      Flips one random birth or survival count of the rule every K generations, logging each change
+  --noise RATE         Chance in [0, 1] that each dead cell is born at random every generation (default 0)
+  --letterbox          Keeps cells in proportion when the window is resized, adding bars
+  --density D          Fills the board at random, each cell alive with chance D in [0, 1]
+  --formula EXPR       Fills the board with the cells where EXPR in x and y is true, like (x+y)%2==0
+  --dead-color R,G,B   Fills dead cells with this color instead of leaving them black
+  --palette PATH       Colors each live cell with the pixel at its coordinates in this image
+  --font PATH          The font for text overlays (default: the first common system font found)
+  --async-scan         Updates cells one at a time in scan order, in place, instead of all at once
+  --rule RULE          Life-like rule in B/S notation (default B3/S23, or B2/S34 on hexagonal cells)
+  --topology KIND      Lays cells out as square (default) or hexagonal, with 6 neighbors each
+  --boundary KIND      Edges to start with: dead (default), torus, klein or projective
+  --rule-table PATH    Loads a totalistic rule table, replacing --rule
+  --apply-rle-rules    Switches to the rule in a pasted or loaded RLE pattern's header without asking
+  --compare RULE       Runs a second board under RULE side by side, from the same start
+  --mirror-edits       With --compare, mouse edits on either board apply to both
+  --log-violations     Prints every generation that puts live cells in forbidden cells
+  --count-escapes      Counts gliders leaving a dead-edged board in --headless and --bench runs too
+  --load PATH          Loads an RLE pattern, a .cells plaintext one or a .csv of x,y rows onto the board at the start
+  --library NAME       Places a built-in pattern, such as pulsar or glider, showing oscillators' phases
+  --cells LIST         Sets cells alive at the start, given as space-separated X,Y pairs like '1,0 2,1 0,2'
+  --placement MODE     Where --load puts it: center, top-left or absolute, from its #P line (default center)
+  --load-offset X,Y    Puts the --load pattern's top-left corner at cell X,Y instead, overriding --placement
+  --symmetry MODE      Mirrors mouse edits: off (default), left-right, top-bottom or 4-fold
+  --symmetry-axis X,Y  The column and row --symmetry mirrors across, in whole or half cells (default the center)
+  --stamp PATH@X,Y     Stamps a pattern with its top-left corner at X,Y; repeatable, warning where they overlap
+  --combine OP:PATH    Combines the board with an RLE pattern placed like --load, OP being and, or, xor or andnot
+  --reference PATH     Loads an RLE file as the board to compare against with D
+  --autosave SECONDS   Saves the board to a temporary file this often, 0 to disable (default 60)
+  --equilibrium K      Reports when the population has stayed in a narrow band for K generations, at most 200
+  --equilibrium-variance V  How narrow: the largest population variance that counts (default 1)
+  --equilibrium-pause  Also pauses when --equilibrium reports
+  --save-on-exit PATH  Saves the board to PATH as RLE when the window is closed
+  --log-population PATH  Appends generation,population,births,deaths to a CSV every generation
+  --framebuffer PATH   Also draws each frame off-screen and keeps its raw RGBA pixels at the start of PATH
+
+Headless mode:
+  --headless                   Runs without a window and prints the final board
+  --until-population-below N   Stops once fewer than N cells are alive
+  --until-population-above N   Stops once more than N cells are alive
+  --until-stable               Stops once the board is a still life or oscillator of period up to --max-period
+  --max-period P               Longest period to look for (default 2); in a window, warns when one is found
+  --max-generations N          Gives up after N generations (default 10000)
+  Any --until-* flag implies --headless.
+
+GIF export:
+  --gif PATH           Records the run as an animated GIF instead of opening a window
+  --generations N      Number of generations to record (default 100)
+  --delay MS           Time between frames in milliseconds (default 100)
+  Pixels per cell are taken from --cell-width and --cell-height.
+
+Benchmark:
+  --bench              Runs flat out without a window and prints generations and cells per second
+  --duration SECONDS   How long to run the benchmark for (default 10)
+  Without --density or --formula, the board is filled at random with density 0.5.
+
+Environment:
+  GOL_WIDTH, GOL_HEIGHT, GOL_RULE and GOL_SEED stand in for --width, --height,
+  --rule and --seed. Flags take precedence, and invalid values are ignored
+  with a warning.
+
+  -h, --help           Prints this message";
+
+pub struct Options {
+    pub board_width: usize,
+    pub board_height: usize,
+    /// A size to crop or extend the board to once it's set up.
+    pub resize_to: Option<(usize, usize)>,
+    pub resize_anchor: Anchor,
+    /// `None` centers the view on the board.
+    pub view_center: Option<Vector2f>,
+    /// `None` fits the whole board in the view.
+    pub view_size: Option<Vector2f>,
+    pub cell_size: Vector2f,
+    pub cell_padding: f32,
+    pub y_up: bool,
+    pub tile_spacing: usize,
+    pub trail_decay: f32,
+    pub activity_window: usize,
+    pub activity_decay: f32,
+    pub start_paused: bool,
+    /// How long the intro takes to reveal the starting cells, or zero for
+    /// no intro.
+    pub reveal_duration: Duration,
+    pub reveal_order: RevealOrder,
+    pub sandbox: bool,
+    pub pause_at: Option<u64>,
+    /// Generations run before the window opens.
+    pub burn_in: u64,
+    /// Pauses once the last live cell in view leaves it.
+    pub pause_when_view_empty: bool,
+    /// Generations run every frame in place of the time-based schedule.
+    pub ticks_per_frame: Option<u32>,
+    pub max_generation: Option<u64>,
+    pub seed: Option<u64>,
+    pub noise_rate: f32,
+    /// Generations between random rule mutations.
+    pub mutation_interval: Option<u64>,
+    pub letterbox: bool,
+    pub density: Option<f32>,
+    pub formula: Option<Formula>,
+    pub dead_cell_color: Option<Color>,
+    pub palette_path: Option<String>,
+    pub font_path: Option<String>,
+    /// `None` until a rule is given, leaving it to `topology`'s usual one.
+    pub rule: Option<Rule>,
+    pub topology: GridTopology,
+    pub boundary: Boundary,
+    pub async_scan: bool,
+    pub rule_table_path: Option<String>,
+    pub apply_rle_rules: bool,
+    /// The rule of a second board shown next to the first.
+    pub compare_rule: Option<Rule>,
+    pub mirror_edits: bool,
+    pub log_violations: bool,
+    pub count_escapes: bool,
+    pub load_path: Option<String>,
+    pub library_pattern: Option<&'static LibraryPattern>,
+    /// Cells set alive at the start, which may be off the board.
+    pub cells: Vec<(isize, isize)>,
+    pub placement: PlacementMode,
+    /// Where `--load` puts its pattern's top-left corner, overriding
+    /// `placement`.
+    pub load_offset: Option<(isize, isize)>,
+    pub symmetry: Symmetry,
+    /// The symmetry brush's axes, doubled like `Game::symmetry_axes`.
+    pub symmetry_axes: Option<(isize, isize)>,
+    /// Patterns combined with the board after `--load`, in order.
+    pub combine: Vec<(BoolOp, String)>,
+    /// Pattern files stamped together at the given corners.
+    pub stamps: Vec<(String, (isize, isize))>,
+    pub reference_path: Option<String>,
+    /// Seconds between autosaves, or 0 for none.
+    pub autosave_interval: u64,
+    /// Where to save the board when the window is closed.
+    pub save_on_exit_path: Option<String>,
+    pub population_log_path: Option<String>,
+    pub framebuffer_path: Option<String>,
+    /// Generations the population must hold steady for an equilibrium
+    /// alert, if one is wanted.
+    pub equilibrium_window: Option<usize>,
+    pub equilibrium_variance: f64,
+    pub equilibrium_pause: bool,
+    pub headless: bool,
+    pub stop_conditions: Vec<StopCondition>,
+    pub max_generations: u64,
+    /// Set only by `--max-period`; headless runs fall back to a default.
+    pub max_period: Option<usize>,
+    /// Where to record an animated GIF, which replaces the window.
+    pub gif_path: Option<String>,
+    pub gif_generations: u64,
+    pub gif_delay_ms: u64,
+    pub bench: bool,
+    pub bench_duration: Duration,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            board_width: DEFAULT_BOARD_SIZE,
+            board_height: DEFAULT_BOARD_SIZE,
+            resize_to: None,
+            resize_anchor: Anchor::TopLeft,
+            view_center: None,
+            view_size: None,
+            cell_size: Vector2f::new(DEFAULT_CELL_SIZE, DEFAULT_CELL_SIZE),
+            cell_padding: 0.0,
+            y_up: false,
+            tile_spacing: 1,
+            trail_decay: DEFAULT_TRAIL_DECAY,
+            activity_window: 100,
+            activity_decay: DEFAULT_ACTIVITY_DECAY,
+            start_paused: false,
+            reveal_duration: Duration::ZERO,
+            reveal_order: RevealOrder::Scan,
+            sandbox: false,
+            pause_at: None,
+            burn_in: 0,
+            pause_when_view_empty: false,
+            ticks_per_frame: None,
+            max_generation: None,
+            seed: None,
+            noise_rate: 0.0,
+            mutation_interval: None,
+            letterbox: false,
+            density: None,
+            formula: None,
+            dead_cell_color: None,
+            palette_path: None,
+            font_path: None,
+            rule: None,
+            topology: GridTopology::Square,
+            boundary: Boundary::Dead,
+            async_scan: false,
+            rule_table_path: None,
+            apply_rle_rules: false,
+            compare_rule: None,
+            mirror_edits: false,
+            log_violations: false,
+            count_escapes: false,
+            load_path: None,
+            library_pattern: None,
+            cells: Vec::new(),
+            placement: PlacementMode::Center,
+            load_offset: None,
+            symmetry: Symmetry::Off,
+            symmetry_axes: None,
+            combine: Vec::new(),
+            stamps: Vec::new(),
+            reference_path: None,
+            autosave_interval: DEFAULT_AUTOSAVE_INTERVAL,
+            save_on_exit_path: None,
+            population_log_path: None,
+            framebuffer_path: None,
+            equilibrium_window: None,
+            equilibrium_variance: DEFAULT_EQUILIBRIUM_VARIANCE,
+            equilibrium_pause: false,
+            headless: false,
+            stop_conditions: Vec::new(),
+            max_generations: DEFAULT_MAX_GENERATIONS,
+            max_period: None,
+            gif_path: None,
+            gif_generations: DEFAULT_GIF_GENERATIONS,
+            gif_delay_ms: DEFAULT_GIF_DELAY_MS,
+            bench: false,
+            bench_duration: Duration::from_secs_f64(DEFAULT_BENCH_SECONDS),
+        }
+    }
+}
+
+pub enum Command {
+    Run(Box<Options>),
+    PrintUsage,
+}
+
+/// Parses `X,Y` into a pair of numbers.
+fn parse_pair<T: FromStr>(value: &str) -> Option<(T, T)> {
+    let (first, second) = value.split_once(',')?;
+    Some((first.trim().parse().ok()?, second.trim().parse().ok()?))
+}
+
+/// Parses `R,G,B` with each channel in `0..=255`.
+fn parse_color(value: &str) -> Option<Color> {
+    let mut channels = value.split(',').map(|channel| channel.trim().parse().ok());
+    let color = Color::rgb(channels.next()??, channels.next()??, channels.next()??);
+    channels.next().is_none().then_some(color)
+}
+
+fn next_value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("{} expects a value", flag))
+}
+
+/// Takes the next argument and parses it, `expected` naming what it should be
+/// for the error message.
+fn next_parsed<T: FromStr>(
+    args: &mut impl Iterator<Item = String>,
+    flag: &str,
+    expected: &str,
+) -> Result<T, String> {
+    let value = next_value(args, flag)?;
+    value
+        .parse()
+        .map_err(|_| format!("{} expects {}, got {:?}", flag, expected, value))
+}
+
+/// Fills in the options the `GOL_*` environment variables set, `var`
+/// looking each one up. A value that doesn't parse is reported and leaves
+/// the default in place, so a stray variable can't stop the program.
+fn apply_environment(options: &mut Options, var: impl Fn(&str) -> Option<String>) {
+    fn parsed<T: FromStr>(
+        var: &impl Fn(&str) -> Option<String>,
+        name: &str,
+        expected: &str,
+    ) -> Option<T> {
+        let value = var(name)?;
+        let parsed = value.trim().parse().ok();
+        if parsed.is_none() {
+            eprintln!("Ignoring {}={:?}, expected {}", name, value, expected);
+        }
+        parsed
+    }
+
+    for (name, size) in [
+        ("GOL_WIDTH", &mut options.board_width),
+        ("GOL_HEIGHT", &mut options.board_height),
+    ] {
+        if let Some(value) = parsed(&var, name, "a number of cells at least 1") {
+            if value > 0 {
+                *size = value;
+            } else {
+                eprintln!("Ignoring {}=0, expected a number of cells at least 1", name);
+            }
+        }
+    }
+    if let Some(rule) = parsed(&var, "GOL_RULE", "a rule like B3/S23") {
+        options.rule = Some(rule);
+    }
+    if let Some(seed) = parsed(&var, "GOL_SEED", "a whole number") {
+        options.seed = Some(seed);
+    }
+}
+
+/// Reads the command line over whatever the environment, looked up through
+/// `var`, already set.
+pub fn parse(
+    mut args: impl Iterator<Item = String>,
+    var: impl Fn(&str) -> Option<String>,
+) -> Result<Command, String> {
+    let mut options = Options::default();
+    apply_environment(&mut options, var);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::PrintUsage),
+            "--width" | "--height" => {
+                let size: usize = next_parsed(&mut args, &arg, "a number of cells")?;
+                if size == 0 {
+                    return Err(format!("{} must be at least 1", arg));
+                }
+                if arg == "--width" {
+                    options.board_width = size;
+                } else {
+                    options.board_height = size;
+                }
+            }
+            "--resize-to" => {
+                let value = next_value(&mut args, &arg)?;
+                let (width, height) = parse_pair(&value)
+                    .filter(|&(width, height)| width > 0 && height > 0)
+                    .ok_or_else(|| {
+                        format!("--resize-to expects a size like 60,40, got {:?}", value)
+                    })?;
+                options.resize_to = Some((width, height));
+            }
+            "--resize-anchor" => {
+                options.resize_anchor = match next_value(&mut args, &arg)?.as_str() {
+                    "top-left" => Anchor::TopLeft,
+                    "center" => Anchor::Center,
+                    other => {
+                        return Err(format!(
+                            "--resize-anchor expects top-left or center, got {:?}",
+                            other
+                        ))
+                    }
+                };
+            }
+            "--view-center" => {
+                let value = next_value(&mut args, &arg)?;
+                let (x, y) = parse_pair(&value)
+                    .ok_or_else(|| format!("--view-center expects X,Y, got {:?}", value))?;
+                options.view_center = Some(Vector2f::new(x, y));
+            }
+            "--view-size" => {
+                let value = next_value(&mut args, &arg)?;
+                let (width, height): (f32, f32) = parse_pair(&value)
+                    .ok_or_else(|| format!("--view-size expects W,H, got {:?}", value))?;
+
+                if width > 0.0 && height > 0.0 {
+                    options.view_size = Some(Vector2f::new(width, height));
+                } else {
+                    eprintln!("--view-size must be positive, fitting the whole board instead");
+                }
+            }
+            "--cell-width" | "--cell-height" => {
+                let size: f32 = next_parsed(&mut args, &arg, "a number")?;
+
+                if size > 0.0 {
+                    if arg == "--cell-width" {
+                        options.cell_size.x = size;
+                    } else {
+                        options.cell_size.y = size;
+                    }
+                } else {
+                    eprintln!(
+                        "{} must be positive, using {} instead",
+                        arg, DEFAULT_CELL_SIZE
+                    );
+                }
+            }
+            "--cell-padding" => {
+                let padding: f32 = next_parsed(&mut args, &arg, "a number")?;
+                if padding < 0.0 {
+                    return Err(format!("--cell-padding can't be negative, got {}", padding));
+                }
+                options.cell_padding = padding;
+            }
+            "--y-up" => options.y_up = true,
+            "--tile-spacing" => {
+                options.tile_spacing = next_parsed(&mut args, &arg, "a number of cells")?;
+            }
+            "--trail-decay" => {
+                let rate: f32 = next_parsed(&mut args, &arg, "a number")?;
+
+                if rate > 0.0 && rate <= 1.0 {
+                    options.trail_decay = rate;
+                } else {
+                    eprintln!(
+                        "--trail-decay must be in (0, 1], using {} instead",
+                        DEFAULT_TRAIL_DECAY
+                    );
+                }
+            }
+            "--activity-window" => {
+                let window: usize = next_parsed(&mut args, &arg, "a number of generations")?;
+                if window == 0 {
+                    return Err("--activity-window must be at least 1".to_string());
+                }
+                options.activity_window = window;
+            }
+            "--activity-decay" => {
+                let rate: f32 = next_parsed(&mut args, &arg, "a number")?;
+
+                if rate > 0.0 && rate <= 1.0 {
+                    options.activity_decay = rate;
+                } else {
+                    eprintln!(
+                        "--activity-decay must be in (0, 1], using {} instead",
+                        DEFAULT_ACTIVITY_DECAY
+                    );
+                }
+            }
+            "--start-paused" => options.start_paused = true,
+            "--reveal" => {
+                let seconds: f64 = next_parsed(&mut args, &arg, "a number of seconds")?;
+                if !(seconds >= 0.0 && seconds.is_finite()) {
+                    return Err(format!("--reveal can't be negative, got {}", seconds));
+                }
+                options.reveal_duration = Duration::from_secs_f64(seconds);
+            }
+            "--reveal-order" => {
+                options.reveal_order = match next_value(&mut args, &arg)?.as_str() {
+                    "scan" => RevealOrder::Scan,
+                    "random" => RevealOrder::Random,
+                    other => {
+                        return Err(format!(
+                            "--reveal-order expects scan or random, got {:?}",
+                            other
+                        ))
+                    }
+                };
+            }
+            "--sandbox" => options.sandbox = true,
+            "--burn-in" => {
+                options.burn_in = next_parsed(&mut args, &arg, "a generation count")?;
+            }
+            "--pause-when-view-empty" => options.pause_when_view_empty = true,
+            "--pause-at" => {
+                options.pause_at = Some(next_parsed(&mut args, &arg, "a generation number")?);
+            }
+            "--ticks-per-frame" => {
+                let count = next_parsed(&mut args, &arg, "a generation count")?;
+                if count == 0 {
+                    return Err("--ticks-per-frame must be at least 1".to_string());
+                }
+                options.ticks_per_frame = Some(count);
+            }
+            "--generation-cap" => {
+                options.max_generation = Some(next_parsed(&mut args, &arg, "a generation count")?);
+            }
+            "--seed" => {
+                options.seed = Some(next_parsed(&mut args, &arg, "a whole number")?);
+            }
+            "--mutate-rule" => {
+                let interval = next_parsed(&mut args, &arg, "a generation count")?;
+                if interval == 0 {
+                    return Err("--mutate-rule must be at least 1".to_string());
+                }
+                options.mutation_interval = Some(interval);
+            }
+            "--noise" => {
+                let rate: f32 = next_parsed(&mut args, &arg, "a number")?;
+
+                if (0.0..=1.0).contains(&rate) {
+                    options.noise_rate = rate;
+                } else {
+                    eprintln!("--noise must be in [0, 1], leaving noise disabled");
+                }
+            }
+            "--letterbox" => options.letterbox = true,
+            "--density" => {
+                let density: f32 = next_parsed(&mut args, &arg, "a number")?;
+                if !(0.0..=1.0).contains(&density) {
+                    return Err(format!("--density must be in [0, 1], got {}", density));
+                }
+                options.density = Some(density);
+            }
+            "--formula" => {
+                let value = next_value(&mut args, &arg)?;
+                let formula = value
+                    .parse()
+                    .map_err(|error| format!("--formula {:?}: {}", value, error))?;
+                options.formula = Some(formula);
+            }
+            "--dead-color" => {
+                let value = next_value(&mut args, &arg)?;
+                let color = parse_color(&value)
+                    .ok_or_else(|| format!("--dead-color expects R,G,B, got {:?}", value))?;
+                options.dead_cell_color = Some(color);
+            }
+            "--async-scan" => options.async_scan = true,
+            "--rule" => {
+                options.rule = Some(next_parsed(&mut args, &arg, "a rule like B3/S23")?);
+            }
+            "--topology" => {
+                options.topology = match next_value(&mut args, &arg)?.as_str() {
+                    "square" => GridTopology::Square,
+                    "hexagonal" => GridTopology::Hexagonal,
+                    other => {
+                        return Err(format!(
+                            "--topology expects square or hexagonal, got {:?}",
+                            other
+                        ))
+                    }
+                };
+            }
+            "--boundary" => {
+                options.boundary = match next_value(&mut args, &arg)?.as_str() {
+                    "dead" => Boundary::Dead,
+                    "torus" => Boundary::Toroidal,
+                    "klein" => Boundary::KleinBottle,
+                    "projective" => Boundary::ProjectivePlane,
+                    other => {
+                        return Err(format!(
+                            "--boundary expects dead, torus, klein or projective, got {:?}",
+                            other
+                        ))
+                    }
+                };
+            }
+            "--rule-table" => options.rule_table_path = Some(next_value(&mut args, &arg)?),
+            "--apply-rle-rules" => options.apply_rle_rules = true,
+            "--compare" => {
+                options.compare_rule = Some(next_parsed(&mut args, &arg, "a rule like B36/S23")?);
+            }
+            "--mirror-edits" => options.mirror_edits = true,
+            "--log-violations" => options.log_violations = true,
+            "--count-escapes" => options.count_escapes = true,
+            "--load" => options.load_path = Some(next_value(&mut args, &arg)?),
+            "--library" => {
+                let name = next_value(&mut args, &arg)?;
+                options.library_pattern = Some(library::find(&name).ok_or_else(|| {
+                    format!(
+                        "--library has no pattern {:?}, expected one of {}",
+                        name,
+                        library::names().join(", ")
+                    )
+                })?);
+            }
+            "--cells" => {
+                let value = next_value(&mut args, &arg)?;
+                for pair in value.split_whitespace() {
+                    let cell = parse_pair(pair).ok_or_else(|| {
+                        format!("--cells expects X,Y pairs, got {:?} in {:?}", pair, value)
+                    })?;
+                    options.cells.push(cell);
+                }
+            }
+            "--stamp" => {
+                let value = next_value(&mut args, &arg)?;
+                let stamp = value
+                    .rsplit_once('@')
+                    .and_then(|(path, corner)| Some((path.to_string(), parse_pair(corner)?)))
+                    .ok_or_else(|| format!("--stamp expects PATH@X,Y, got {:?}", value))?;
+                options.stamps.push(stamp);
+            }
+            "--combine" => {
+                let value = next_value(&mut args, &arg)?;
+                let (op, path) = value
+                    .split_once(':')
+                    .ok_or_else(|| format!("--combine expects OP:PATH, got {:?}", value))?;
+                let op = match op {
+                    "and" => BoolOp::And,
+                    "or" => BoolOp::Or,
+                    "xor" => BoolOp::Xor,
+                    "andnot" => BoolOp::AndNot,
+                    other => {
+                        return Err(format!(
+                            "--combine expects and, or, xor or andnot, got {:?}",
+                            other
+                        ))
+                    }
+                };
+                options.combine.push((op, path.to_string()));
+            }
+            "--load-offset" => {
+                let value = next_value(&mut args, &arg)?;
+                let offset = parse_pair(&value).ok_or_else(|| {
+                    format!("--load-offset expects a cell like 10,20, got {:?}", value)
+                })?;
+                options.load_offset = Some(offset);
+            }
+            "--placement" => {
+                options.placement = match next_value(&mut args, &arg)?.as_str() {
+                    "center" => PlacementMode::Center,
+                    "top-left" => PlacementMode::TopLeft,
+                    "absolute" => PlacementMode::Absolute,
+                    other => {
+                        return Err(format!(
+                            "--placement expects center, top-left or absolute, got {:?}",
+                            other
+                        ))
+                    }
+                };
+            }
+            "--symmetry" => {
+                options.symmetry = match next_value(&mut args, &arg)?.as_str() {
+                    "off" => Symmetry::Off,
+                    "left-right" => Symmetry::LeftRight,
+                    "top-bottom" => Symmetry::TopBottom,
+                    "4-fold" => Symmetry::FourFold,
+                    other => {
+                        return Err(format!(
+                            "--symmetry expects off, left-right, top-bottom or 4-fold, got {:?}",
+                            other
+                        ))
+                    }
+                };
+            }
+            "--symmetry-axis" => {
+                let value = next_value(&mut args, &arg)?;
+                let (x, y): (f32, f32) = parse_pair(&value).ok_or_else(|| {
+                    format!("--symmetry-axis expects X,Y like 19.5,20, got {:?}", value)
+                })?;
+                let doubled = |coordinate: f32| -> Result<isize, String> {
+                    let doubled = coordinate * 2.0;
+                    if doubled.fract() == 0.0 && doubled.is_finite() {
+                        Ok(doubled as isize)
+                    } else {
+                        Err(format!(
+                            "--symmetry-axis must be whole or half cells, got {}",
+                            coordinate
+                        ))
+                    }
+                };
+                options.symmetry_axes = Some((doubled(x)?, doubled(y)?));
+            }
+            "--reference" => options.reference_path = Some(next_value(&mut args, &arg)?),
+            "--autosave" => {
+                options.autosave_interval = next_parsed(&mut args, &arg, "a number of seconds")?;
+            }
+            "--save-on-exit" => options.save_on_exit_path = Some(next_value(&mut args, &arg)?),
+            "--log-population" => {
+                options.population_log_path = Some(next_value(&mut args, &arg)?);
+            }
+            "--framebuffer" => options.framebuffer_path = Some(next_value(&mut args, &arg)?),
+            "--equilibrium" => {
+                let window: usize = next_parsed(&mut args, &arg, "a generation count")?;
+                if !(2..=POPULATION_HISTORY_LENGTH).contains(&window) {
+                    return Err(format!(
+                        "--equilibrium must be between 2 and {}, got {}",
+                        POPULATION_HISTORY_LENGTH, window
+                    ));
+                }
+                options.equilibrium_window = Some(window);
+            }
+            "--equilibrium-variance" => {
+                let variance: f64 = next_parsed(&mut args, &arg, "a number")?;
+                if !(variance >= 0.0 && variance.is_finite()) {
+                    return Err(format!(
+                        "--equilibrium-variance can't be negative, got {}",
+                        variance
+                    ));
+                }
+                options.equilibrium_variance = variance;
+            }
+            "--equilibrium-pause" => options.equilibrium_pause = true,
+            "--palette" => options.palette_path = Some(next_value(&mut args, &arg)?),
+            "--font" => options.font_path = Some(next_value(&mut args, &arg)?),
+            "--headless" => options.headless = true,
+            "--until-population-below" => {
+                let population = next_parsed(&mut args, &arg, "a cell count")?;
+                options
+                    .stop_conditions
+                    .push(StopCondition::PopulationBelow(population));
+            }
+            "--until-population-above" => {
+                let population = next_parsed(&mut args, &arg, "a cell count")?;
+                options
+                    .stop_conditions
+                    .push(StopCondition::PopulationAbove(population));
+            }
+            "--until-stable" => options.stop_conditions.push(StopCondition::Stable),
+            "--max-generations" => {
+                options.max_generations = next_parsed(&mut args, &arg, "a generation count")?;
+            }
+            "--max-period" => {
+                let period = next_parsed(&mut args, &arg, "a generation count")?;
+                if period == 0 {
+                    return Err("--max-period must be at least 1".to_string());
+                }
+                options.max_period = Some(period);
+            }
+            "--gif" => options.gif_path = Some(next_value(&mut args, &arg)?),
+            "--generations" => {
+                options.gif_generations = next_parsed(&mut args, &arg, "a generation count")?;
+            }
+            "--delay" => {
+                options.gif_delay_ms = next_parsed(&mut args, &arg, "a number of milliseconds")?;
+            }
+            "--bench" => options.bench = true,
+            "--duration" => {
+                let seconds: f64 = next_parsed(&mut args, &arg, "a number of seconds")?;
+                if !(seconds > 0.0 && seconds.is_finite()) {
+                    return Err(format!("--duration must be positive, got {}", seconds));
+                }
+                options.bench_duration = Duration::from_secs_f64(seconds);
+            }
+            _ => return Err(format!("Unknown argument {:?}", arg)),
+        }
+    }
+
+    if !options
+        .topology
+        .fits(options.boundary, options.board_height)
+    {
+        return Err(format!(
+            "--topology hexagonal with --boundary other than dead needs an even --height, got {}",
+            options.board_height
+        ));
+    }
+    if !options.stop_conditions.is_empty() {
+        options.headless = true;
+    }
+    if options.bench && options.density.is_none() && options.formula.is_none() {
+        options.density = Some(DEFAULT_BENCH_DENSITY);
+    }
+
+    Ok(Command::Run(Box::new(options)))
+}
@@ -0,0 +1,124 @@
+use conways_gol::{game::Game, overlay};
+use sfml::{
+    graphics::{FloatRect, Font, RenderTarget, RenderWindow, View},
+    system::Vector2f,
+    window::Event,
+    SfBox,
+};
+
+/// The halves of the window the two boards are drawn in, as fractions of it.
+const LEFT_PANE: FloatRect = FloatRect::new(0.0, 0.0, 0.5, 1.0);
+const RIGHT_PANE: FloatRect = FloatRect::new(0.5, 0.0, 0.5, 1.0);
+
+/// `view` squeezed into `pane`, keeping whatever viewport it already has
+/// (such as letterboxing) relative to the pane.
+fn pane_view(view: &View, pane: FloatRect) -> SfBox<View> {
+    let viewport = view.viewport();
+    let mut pane_view = view.to_owned();
+    pane_view.set_viewport(&FloatRect::new(
+        pane.left + viewport.left * pane.width,
+        pane.top + viewport.top * pane.height,
+        viewport.width * pane.width,
+        viewport.height * pane.height,
+    ));
+    pane_view
+}
+
+/// `view` as it's shown in the pane that window column `x` falls in.
+pub fn pane_view_at(view: &View, window: &RenderWindow, x: i32) -> SfBox<View> {
+    let pane = if x < window.size().x as i32 / 2 {
+        LEFT_PANE
+    } else {
+        RIGHT_PANE
+    };
+    pane_view(view, pane)
+}
+
+/// The same mouse event, moved `dx` pixels sideways.
+fn shifted(event: &Event, dx: i32) -> Event {
+    match *event {
+        Event::MouseButtonPressed { button, x, y } => Event::MouseButtonPressed {
+            button,
+            x: x + dx,
+            y,
+        },
+        Event::MouseButtonReleased { button, x, y } => Event::MouseButtonReleased {
+            button,
+            x: x + dx,
+            y,
+        },
+        Event::MouseMoved { x, y } => Event::MouseMoved { x: x + dx, y },
+        event => event,
+    }
+}
+
+fn pointer_x(event: &Event) -> Option<i32> {
+    match *event {
+        Event::MouseButtonPressed { x, .. }
+        | Event::MouseButtonReleased { x, .. }
+        | Event::MouseMoved { x, .. } => Some(x),
+        _ => None,
+    }
+}
+
+/// Hands `event` to the boards: mouse events to the one under the pointer
+/// (and, with `mirror_edits`, to the same spot on the other), everything
+/// else to both so they stay in lockstep.
+pub fn dispatch(
+    event: &Event,
+    left: &mut Game,
+    right: &mut Game,
+    window: &mut RenderWindow,
+    view: &View,
+    mirror_edits: bool,
+) {
+    let Some(x) = pointer_x(event) else {
+        left.process_event(event, window);
+        right.process_event(event, window);
+        return;
+    };
+
+    let half = window.size().x as i32 / 2;
+    let (target, target_pane, other, other_pane, dx) = if x < half {
+        (left, LEFT_PANE, right, RIGHT_PANE, half)
+    } else {
+        (right, RIGHT_PANE, left, LEFT_PANE, -half)
+    };
+
+    window.set_view(&pane_view(view, target_pane));
+    target.process_event(event, window);
+
+    if mirror_edits {
+        window.set_view(&pane_view(view, other_pane));
+        other.process_event(&shifted(event, dx), window);
+    }
+}
+
+/// Draws each board in its half of the window, labeled with its rule.
+pub fn draw(
+    window: &mut RenderWindow,
+    font: Option<&Font>,
+    left: &Game,
+    right: &Game,
+    view: &View,
+) {
+    let width = window.size().x as f32;
+
+    for (game, pane) in [(left, LEFT_PANE), (right, RIGHT_PANE)] {
+        window.set_view(&pane_view(view, pane));
+        game.draw(window);
+
+        if let Some(font) = font {
+            overlay::draw_selection_size(window, font, game);
+            overlay::draw_inspector(window, font, game);
+            overlay::draw_caption(
+                window,
+                font,
+                &game.rule.to_string(),
+                Vector2f::new(pane.left * width + 10.0, 10.0),
+            );
+        }
+    }
+
+    window.set_view(&pane_view(view, LEFT_PANE));
+}
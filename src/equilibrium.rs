@@ -0,0 +1,64 @@
+use conways_gol::game::Game;
+
+/// Watches for a population that has settled into a narrow band, which
+/// takes in chaotic but steady boards that never repeat exactly.
+pub struct EquilibriumAlert {
+    /// How many recent generations must stay in the band.
+    window: usize,
+    /// The largest population variance, in cells squared, still counted as
+    /// settled.
+    max_variance: f64,
+    /// Pauses the game as well as reporting.
+    pause: bool,
+    /// Whether the band currently holds, so each settling is reported once.
+    settled: bool,
+    last_generation: u64,
+}
+
+impl EquilibriumAlert {
+    /// `window` must be no more than `POPULATION_HISTORY_LENGTH`, since the
+    /// populations are read from `Game::population_history`.
+    pub fn new(window: usize, max_variance: f64, pause: bool) -> EquilibriumAlert {
+        EquilibriumAlert {
+            window,
+            max_variance,
+            pause,
+            settled: false,
+            last_generation: 0,
+        }
+    }
+
+    /// Looks at the populations since the last check, reporting on stdout
+    /// when the last `window` of them first fall inside the band.
+    pub fn check(&mut self, game: &mut Game) {
+        if game.generation == self.last_generation {
+            return;
+        }
+        self.last_generation = game.generation;
+
+        let history = &game.population_history;
+        if history.len() < self.window {
+            self.settled = false;
+            return;
+        }
+        let recent = history.iter().skip(history.len() - self.window);
+        let mean = recent.clone().sum::<usize>() as f64 / self.window as f64;
+        let variance = recent
+            .map(|&population| (population as f64 - mean).powi(2))
+            .sum::<f64>()
+            / self.window as f64;
+
+        // An empty board is as steady as it gets, but not worth an alert.
+        let settled = variance <= self.max_variance && mean > 0.0;
+        if settled && !self.settled {
+            println!(
+                "Generation {}: population has held near {:.0} for {} generations (variance {:.2})",
+                game.generation, mean, self.window, variance
+            );
+            if self.pause {
+                game.paused = true;
+            }
+        }
+        self.settled = settled;
+    }
+}
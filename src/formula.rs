@@ -0,0 +1,337 @@
+use std::{fmt, str::FromStr};
+
+/// An arithmetic expression in the cell coordinates `x` and `y`, such as
+/// `(x + y) % 2 == 0` or `sin(x / 3) > y / 20 - 1`.
+///
+/// Everything is a number. Comparisons and `!`, `&&`, `||` give 1 for true
+/// and 0 for false, and any non-zero result counts as true. `width` and
+/// `height` are the board's size, and `sin`, `cos`, `abs`, `sqrt` and
+/// `floor` take one argument each. `%` is always non-negative, so patterns
+/// don't flip at zero.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Formula {
+    root: Expression,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Variable {
+    X,
+    Y,
+    Width,
+    Height,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Function {
+    Sin,
+    Cos,
+    Abs,
+    Sqrt,
+    Floor,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Operator {
+    Or,
+    And,
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Expression {
+    Number(f64),
+    Variable(Variable),
+    Negate(Box<Expression>),
+    Not(Box<Expression>),
+    Call(Function, Box<Expression>),
+    Binary(Operator, Box<Expression>, Box<Expression>),
+}
+
+/// The values a formula's variables take for one cell.
+struct Cell {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+fn truth(value: bool) -> f64 {
+    if value {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+fn is_true(value: f64) -> bool {
+    value != 0.0 && !value.is_nan()
+}
+
+impl Expression {
+    fn eval(&self, cell: &Cell) -> f64 {
+        match self {
+            Expression::Number(value) => *value,
+            Expression::Variable(variable) => match variable {
+                Variable::X => cell.x,
+                Variable::Y => cell.y,
+                Variable::Width => cell.width,
+                Variable::Height => cell.height,
+            },
+            Expression::Negate(operand) => -operand.eval(cell),
+            Expression::Not(operand) => truth(!is_true(operand.eval(cell))),
+            Expression::Call(function, argument) => {
+                let argument = argument.eval(cell);
+                match function {
+                    Function::Sin => argument.sin(),
+                    Function::Cos => argument.cos(),
+                    Function::Abs => argument.abs(),
+                    Function::Sqrt => argument.sqrt(),
+                    Function::Floor => argument.floor(),
+                }
+            }
+            Expression::Binary(operator, left, right) => {
+                let (a, b) = (left.eval(cell), right.eval(cell));
+                match operator {
+                    Operator::Or => truth(is_true(a) || is_true(b)),
+                    Operator::And => truth(is_true(a) && is_true(b)),
+                    Operator::Equal => truth(a == b),
+                    Operator::NotEqual => truth(a != b),
+                    Operator::Less => truth(a < b),
+                    Operator::LessOrEqual => truth(a <= b),
+                    Operator::Greater => truth(a > b),
+                    Operator::GreaterOrEqual => truth(a >= b),
+                    Operator::Add => a + b,
+                    Operator::Subtract => a - b,
+                    Operator::Multiply => a * b,
+                    Operator::Divide => a / b,
+                    Operator::Remainder => a.rem_euclid(b),
+                }
+            }
+        }
+    }
+}
+
+impl Formula {
+    /// Whether the cell at `(x, y)` on a `width` by `height` board should be
+    /// alive. Results that aren't numbers, like `0 / 0`, count as false.
+    pub fn is_alive(&self, x: usize, y: usize, width: usize, height: usize) -> bool {
+        is_true(self.root.eval(&Cell {
+            x: x as f64,
+            y: y as f64,
+            width: width as f64,
+            height: height as f64,
+        }))
+    }
+}
+
+/// A formula that couldn't be read, and the character offset it went wrong
+/// at.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct InvalidFormula {
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for InvalidFormula {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at character {}", self.message, self.position + 1)
+    }
+}
+
+impl std::error::Error for InvalidFormula {}
+
+/// A recursive descent parser, one method per precedence level from
+/// loosest to tightest.
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+const COMPARISONS: &[(&str, Operator)] = &[
+    ("==", Operator::Equal),
+    ("!=", Operator::NotEqual),
+    ("<=", Operator::LessOrEqual),
+    (">=", Operator::GreaterOrEqual),
+    ("<", Operator::Less),
+    (">", Operator::Greater),
+];
+const SUMS: &[(&str, Operator)] = &[("+", Operator::Add), ("-", Operator::Subtract)];
+const PRODUCTS: &[(&str, Operator)] = &[
+    ("*", Operator::Multiply),
+    ("/", Operator::Divide),
+    ("%", Operator::Remainder),
+];
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> InvalidFormula {
+        InvalidFormula {
+            position: self.position,
+            message: message.to_string(),
+        }
+    }
+
+    fn rest(&self) -> &str {
+        &self.text[self.position..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let trimmed = self.rest().trim_start();
+        self.position = self.text.len() - trimmed.len();
+    }
+
+    /// Consumes `token` if it comes next.
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(token) {
+            self.position += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consumes whichever of `operators` comes next, trying them in order.
+    fn operator(&mut self, operators: &[(&str, Operator)]) -> Option<Operator> {
+        operators
+            .iter()
+            .find(|(token, _)| self.eat(token))
+            .map(|&(_, operator)| operator)
+    }
+
+    /// Parses a left-associative chain of `operators` between operands
+    /// parsed by `operand`.
+    fn chain(
+        &mut self,
+        operators: &[(&str, Operator)],
+        operand: fn(&mut Self) -> Result<Expression, InvalidFormula>,
+    ) -> Result<Expression, InvalidFormula> {
+        let mut left = operand(self)?;
+        while let Some(operator) = self.operator(operators) {
+            left = Expression::Binary(operator, Box::new(left), Box::new(operand(self)?));
+        }
+        Ok(left)
+    }
+
+    fn or(&mut self) -> Result<Expression, InvalidFormula> {
+        self.chain(&[("||", Operator::Or)], Self::and)
+    }
+
+    fn and(&mut self) -> Result<Expression, InvalidFormula> {
+        self.chain(&[("&&", Operator::And)], Self::comparison)
+    }
+
+    fn comparison(&mut self) -> Result<Expression, InvalidFormula> {
+        self.chain(COMPARISONS, Self::sum)
+    }
+
+    fn sum(&mut self) -> Result<Expression, InvalidFormula> {
+        self.chain(SUMS, Self::product)
+    }
+
+    fn product(&mut self) -> Result<Expression, InvalidFormula> {
+        self.chain(PRODUCTS, Self::unary)
+    }
+
+    fn unary(&mut self) -> Result<Expression, InvalidFormula> {
+        if self.eat("-") {
+            Ok(Expression::Negate(Box::new(self.unary()?)))
+        } else if self.rest().starts_with("!=") {
+            Err(self.error("expected a value"))
+        } else if self.eat("!") {
+            Ok(Expression::Not(Box::new(self.unary()?)))
+        } else {
+            self.primary()
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expression, InvalidFormula> {
+        self.skip_whitespace();
+
+        if self.eat("(") {
+            let inner = self.or()?;
+            if !self.eat(")") {
+                return Err(self.error("expected `)`"));
+            }
+            return Ok(inner);
+        }
+
+        let start = self.position;
+        let length = self
+            .rest()
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '.' || c == '_'))
+            .unwrap_or(self.rest().len());
+        let word = &self.text[start..start + length];
+        if word.is_empty() {
+            return Err(self.error("expected a value"));
+        }
+        self.position += length;
+
+        if word.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+            return word
+                .parse()
+                .map(Expression::Number)
+                .map_err(|_| InvalidFormula {
+                    position: start,
+                    message: format!("invalid number {:?}", word),
+                });
+        }
+
+        let variable = match word {
+            "x" => Some(Variable::X),
+            "y" => Some(Variable::Y),
+            "width" => Some(Variable::Width),
+            "height" => Some(Variable::Height),
+            _ => None,
+        };
+        if let Some(variable) = variable {
+            return Ok(Expression::Variable(variable));
+        }
+
+        let function = match word {
+            "sin" => Function::Sin,
+            "cos" => Function::Cos,
+            "abs" => Function::Abs,
+            "sqrt" => Function::Sqrt,
+            "floor" => Function::Floor,
+            _ => {
+                return Err(InvalidFormula {
+                    position: start,
+                    message: format!("unknown name {:?}", word),
+                })
+            }
+        };
+        if !self.eat("(") {
+            return Err(self.error("expected `(` after a function name"));
+        }
+        let argument = self.or()?;
+        if !self.eat(")") {
+            return Err(self.error("expected `)`"));
+        }
+        Ok(Expression::Call(function, Box::new(argument)))
+    }
+}
+
+impl FromStr for Formula {
+    type Err = InvalidFormula;
+
+    fn from_str(text: &str) -> Result<Formula, InvalidFormula> {
+        let mut parser = Parser { text, position: 0 };
+        let root = parser.or()?;
+
+        parser.skip_whitespace();
+        if !parser.rest().is_empty() {
+            return Err(parser.error("unexpected text"));
+        }
+        Ok(Formula { root })
+    }
+}
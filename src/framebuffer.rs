@@ -0,0 +1,37 @@
+use sfml::{
+    graphics::{Color, FloatRect, Image, RenderTarget, RenderTexture, View},
+    system::Vector2u,
+};
+
+use crate::game::Game;
+
+/// An off-screen target the board can be drawn to, for handing frames to
+/// something other than the window, such as capture or streaming software.
+pub struct Framebuffer {
+    texture: RenderTexture,
+}
+
+impl Framebuffer {
+    /// A `width` by `height` pixel buffer, or `None` if SFML can't make one.
+    pub fn new(width: u32, height: u32) -> Option<Framebuffer> {
+        RenderTexture::new(width, height, false).map(|texture| Framebuffer { texture })
+    }
+
+    pub fn size(&self) -> Vector2u {
+        self.texture.size()
+    }
+
+    /// Draws `game` with `Game::draw`, its whole board stretched over the
+    /// buffer, on a `background` fill. The pixels come back as RGBA, one row
+    /// at a time from the top, or `None` if they couldn't be read back.
+    pub fn render(&mut self, game: &Game, background: Color) -> Option<Image> {
+        let world = game.world_size();
+        self.texture.set_view(&View::from_rect(&FloatRect::new(
+            0.0, 0.0, world.x, world.y,
+        )));
+        self.texture.clear(background);
+        game.draw(&mut self.texture);
+        self.texture.display();
+        self.texture.texture().copy_to_image()
+    }
+}
@@ -0,0 +1,3103 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeSet, HashMap, HashSet, VecDeque},
+    fmt,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    time::Instant,
+};
+
+use sfml::{
+    graphics::{
+        Color, FloatRect, Image, RectangleShape, RenderTarget, RenderWindow, Shape, Transformable,
+        View,
+    },
+    system::{Clock, Time, Vector2, Vector2f, Vector2i},
+    window::{clipboard, mouse::Button, Event},
+};
+
+use crate::{
+    ash::{self, CellMotion},
+    bindings::{self, Action},
+    census,
+    formula::Formula,
+    grid::{Anchor, Axis, BoolGrid2D, BoolOp, Boundary, GridTopology},
+    library::LibraryPattern,
+    overlay,
+    packed::PackedGrid,
+    rle,
+    rng::{self, Rng},
+    rule::Rule,
+    rule_table::RuleTable,
+};
+
+/// Opacity of a fresh trail mark; trails stay dim next to live cells.
+const TRAIL_MAX_ALPHA: f32 = 96.0;
+/// Opacity of the hottest cells on the activity map, low enough that live
+/// cells still stand out over it.
+const ACTIVITY_MAX_ALPHA: f32 = 160.0;
+
+/// How much one key press changes the noise rate.
+const NOISE_RATE_STEP: f32 = 0.001;
+const MAX_NOISE_RATE: f32 = 0.05;
+
+/// How much one key press changes the gap between drawn cells.
+const CELL_PADDING_STEP: f32 = 1.0;
+
+/// How many generations the population graph looks back.
+pub const POPULATION_HISTORY_LENGTH: usize = 200;
+
+/// How many cells one press of the grow key adds on every side.
+const GROW_MARGIN: usize = 10;
+
+/// Where the flush key appends probe readings.
+const PROBE_LOG_PATH: &str = "probes.log";
+
+/// The most cells `Game::try_new` will allocate a board for. Every board
+/// keeps several per-cell buffers, so this is already well over a gigabyte.
+pub const MAX_BOARD_CELLS: usize = 1 << 26;
+
+/// Everything `Game::try_new` needs up front. The defaults are what
+/// `Game::new` uses.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct GameConfig {
+    pub width: usize,
+    pub height: usize,
+    pub rule: Rule,
+    pub boundary: Boundary,
+    pub topology: GridTopology,
+    pub cell_size: Vector2f,
+    pub cell_color: Color,
+    /// When set, dead cells are filled with this instead of the background.
+    pub dead_cell_color: Option<Color>,
+}
+
+impl Default for GameConfig {
+    fn default() -> GameConfig {
+        GameConfig {
+            width: 100,
+            height: 100,
+            rule: Rule::CONWAY,
+            boundary: Boundary::Dead,
+            topology: GridTopology::Square,
+            cell_size: Vector2f::new(10.0, 10.0),
+            cell_color: Color::rgb(255, 255, 255),
+            dead_cell_color: None,
+        }
+    }
+}
+
+/// Why `Game::try_new` turned a `GameConfig` down.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GameError {
+    /// The board would have no cells.
+    EmptyBoard { width: usize, height: usize },
+    /// The board would have more than `MAX_BOARD_CELLS` cells.
+    BoardTooLarge { width: usize, height: usize },
+    /// A cell would be drawn with no area, or a size that isn't a number.
+    InvalidCellSize(Vector2f),
+    /// A hexagonal board with an odd number of rows can't wrap its edges.
+    OddHexagonalHeight { height: usize, boundary: Boundary },
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameError::EmptyBoard { width, height } => {
+                write!(f, "a {}x{} board has no cells", width, height)
+            }
+            GameError::BoardTooLarge { width, height } => write!(
+                f,
+                "a {}x{} board is larger than the limit of {} cells",
+                width, height, MAX_BOARD_CELLS
+            ),
+            GameError::InvalidCellSize(size) => write!(
+                f,
+                "cell size {}x{} isn't positive and finite",
+                size.x, size.y
+            ),
+            GameError::OddHexagonalHeight { height, boundary } => write!(
+                f,
+                "a hexagonal board on a {} needs an even height, not {}",
+                boundary, height
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
+
+/// Where `load_pattern` puts a pattern on the board.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum PlacementMode {
+    /// In the middle of the board.
+    #[default]
+    Center,
+    /// With its top-left cell on the board's.
+    TopLeft,
+    /// At the coordinates its file gives, with the board's top-left cell as
+    /// the origin.
+    Absolute,
+}
+
+/// Which mirror images of a cell edited with the mouse are set along with
+/// it, for drawing symmetric patterns.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Symmetry {
+    #[default]
+    Off,
+    /// Mirrored across a vertical axis.
+    LeftRight,
+    /// Mirrored across a horizontal axis.
+    TopBottom,
+    /// Mirrored across both axes, for 4-fold symmetry.
+    FourFold,
+}
+
+impl Symmetry {
+    pub fn next(self) -> Symmetry {
+        match self {
+            Symmetry::Off => Symmetry::LeftRight,
+            Symmetry::LeftRight => Symmetry::TopBottom,
+            Symmetry::TopBottom => Symmetry::FourFold,
+            Symmetry::FourFold => Symmetry::Off,
+        }
+    }
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Symmetry::Off => "off",
+            Symmetry::LeftRight => "left-right",
+            Symmetry::TopBottom => "top-bottom",
+            Symmetry::FourFold => "4-fold",
+        })
+    }
+}
+
+/// How `update` moves the board to its next generation.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum UpdateMode {
+    /// Every cell changes at once, from the previous generation's states.
+    Synchronous,
+    /// Cells change one at a time in scan order, each seeing the new states
+    /// of the neighbors above and to its left.
+    AsyncScan,
+}
+
+impl UpdateMode {
+    pub fn toggled(self) -> UpdateMode {
+        match self {
+            UpdateMode::Synchronous => UpdateMode::AsyncScan,
+            UpdateMode::AsyncScan => UpdateMode::Synchronous,
+        }
+    }
+}
+
+impl fmt::Display for UpdateMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            UpdateMode::Synchronous => "synchronous",
+            UpdateMode::AsyncScan => "async scan",
+        })
+    }
+}
+
+/// A mouse drag that sets every cell it passes over to `alive`. A press
+/// and release without leaving the first cell is a click instead.
+#[derive(Clone, Copy, Debug)]
+struct Stroke {
+    alive: bool,
+    last: Vector2<usize>,
+    moved: bool,
+}
+
+/// A rectangle of cells picked by shift-right-dragging. `start` is where the
+/// drag began, so it isn't necessarily the top-left corner.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Selection {
+    pub start: Vector2<usize>,
+    pub end: Vector2<usize>,
+}
+
+impl Selection {
+    /// The top-left and bottom-right cells of the selection, inclusive.
+    pub fn corners(&self) -> (Vector2<usize>, Vector2<usize>) {
+        (
+            Vector2::new(self.start.x.min(self.end.x), self.start.y.min(self.end.y)),
+            Vector2::new(self.start.x.max(self.end.x), self.start.y.max(self.end.y)),
+        )
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        let (top_left, bottom_right) = self.corners();
+        (top_left.x..=bottom_right.x).contains(&x) && (top_left.y..=bottom_right.y).contains(&y)
+    }
+
+    /// Width and height in cells.
+    pub fn size(&self) -> Vector2<usize> {
+        let (top_left, bottom_right) = self.corners();
+        Vector2::new(
+            bottom_right.x + 1 - top_left.x,
+            bottom_right.y + 1 - top_left.y,
+        )
+    }
+}
+
+/// A probed cell's state after one generation.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ProbeReading {
+    pub generation: u64,
+    pub x: usize,
+    pub y: usize,
+    pub alive: bool,
+}
+
+/// A board saved by hand so it can be returned to later.
+#[derive(Clone, Debug)]
+pub struct Checkpoint {
+    pub grid: BoolGrid2D,
+    pub generation: u64,
+}
+
+/// The cells on a straight line from `(x0, y0)` to `(x1, y1)`, both ends
+/// included, by Bresenham's algorithm.
+fn line_points(x0: isize, y0: isize, x1: isize, y1: isize) -> Vec<(isize, isize)> {
+    let (dx, dy) = ((x1 - x0).abs(), -(y1 - y0).abs());
+    let (step_x, step_y) = ((x1 - x0).signum(), (y1 - y0).signum());
+    let mut error = dx + dy;
+    let (mut x, mut y) = (x0, y0);
+    let mut points = Vec::with_capacity(dx.max(-dy) as usize + 1);
+
+    loop {
+        points.push((x, y));
+        if (x, y) == (x1, y1) {
+            return points;
+        }
+
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if doubled <= dx {
+            error += dx;
+            y += step_y;
+        }
+    }
+}
+
+/// A distinct, bright color for each cluster id. Stepping the hue by the
+/// golden ratio keeps neighboring ids far apart on the color wheel.
+fn component_color(id: usize) -> Color {
+    let hue = (id as f32 * 0.618_034).fract() * 6.0;
+    let rising = (hue.fract() * 255.0) as u8;
+    let falling = 255 - rising;
+
+    match hue as u32 {
+        0 => Color::rgb(255, rising, 0),
+        1 => Color::rgb(falling, 255, 0),
+        2 => Color::rgb(0, 255, rising),
+        3 => Color::rgb(0, falling, 255),
+        4 => Color::rgb(rising, 0, 255),
+        _ => Color::rgb(255, 0, falling),
+    }
+}
+
+/// `color` for state 1 of a `states`-state rule table, fading out towards
+/// the last state so cells on their way back to 0 stand apart.
+fn fade_by_state(mut color: Color, state: u8, states: usize) -> Color {
+    if states > 2 {
+        let remaining = states - state as usize;
+        *color.alpha_mut() = (color.alpha() as usize * remaining / (states - 1)) as u8;
+    }
+    color
+}
+
+/// A color for `level` in `0.0..=1.0` on a heatmap running from deep blue
+/// through red to yellow.
+fn heat_color(level: f32) -> Color {
+    let level = level.clamp(0.0, 1.0);
+    if level < 0.5 {
+        let rising = (level * 2.0 * 255.0) as u8;
+        Color::rgb(rising, 0, 255 - rising)
+    } else {
+        let rising = ((level - 0.5) * 2.0 * 255.0) as u8;
+        Color::rgb(255, rising, 0)
+    }
+}
+
+/// A function `Game` calls when something happens, for embedders that want
+/// to log, collect metrics or drive their own UI.
+pub type Callback = Box<dyn FnMut(&Game)>;
+
+/// Registered callbacks, one list per kind of event.
+#[derive(Default)]
+struct Callbacks {
+    generation: Vec<Callback>,
+    clear: Vec<Callback>,
+    stable: Vec<Callback>,
+}
+
+pub struct Game {
+    pub grid: BoolGrid2D,
+    simulation_grid: BoolGrid2D,
+    pub paused: bool,
+    /// Stops `update` from ever running, whatever `paused` says, for editing
+    /// patterns without any risk of setting them off.
+    pub sandbox: bool,
+    /// Real time between generations while running.
+    pub tick_duration: Time,
+    /// Ignores `tick_duration` and advances one generation every frame, as
+    /// fast as frames can be drawn. Every frame still counts as exactly one
+    /// generation, so comparing `generation` between runs stays meaningful
+    /// whatever the frame rate was.
+    pub max_speed: bool,
+    /// Number of generations simulated since the game started.
+    pub generation: u64,
+    /// When set, the game pauses itself after this many more generations.
+    pub remaining_ticks: Option<u64>,
+    /// A ceiling on how many generations may pass since the board was last
+    /// cleared. Unlike `remaining_ticks` it stays in force after pausing,
+    /// so the run can't be resumed past it without clearing.
+    pub max_generation: Option<u64>,
+    /// The generation the board was last cleared at.
+    cleared_at: u64,
+    /// Cells that came to life since the board was last cleared, counting
+    /// random births from noise.
+    pub total_births: u64,
+    /// Cells that died since the board was last cleared.
+    pub total_deaths: u64,
+    /// Gliders that ran off the edge of a dead boundary since the board was
+    /// last cleared, such as a gun's output.
+    pub escaped_gliders: u64,
+    /// Whether `escaped_gliders` is kept up to date. It costs a little every
+    /// generation, so only windowed runs and `--count-escapes` turn it on.
+    pub count_escaped_gliders: bool,
+    /// Cells the simulation may not change. Live locked cells act as
+    /// permanent walls or emitters.
+    pub locked: BoolGrid2D,
+    /// Cells no live cell should enter, for puzzles built on the board. The
+    /// simulation ignores them; live cells in them are drawn in
+    /// `forbidden_color`.
+    pub forbidden: BoolGrid2D,
+    /// Prints a line for every generation that puts live cells in
+    /// `forbidden`.
+    pub log_violations: bool,
+    pub boundary: Boundary,
+    pub topology: GridTopology,
+    pub update_mode: UpdateMode,
+    pub rule: Rule,
+    /// When set, `update` runs this table over `cell_states` instead of
+    /// `rule`, for the whole board. Set it through `set_rule_table`.
+    rule_table: Option<RuleTable>,
+    /// Each cell's state under `rule_table`, indexed like `grid`. `grid`
+    /// holds whether a state is non-zero, so cells drawn or loaded onto it
+    /// start at state 1.
+    cell_states: Vec<u8>,
+    /// Switches to the rule in a pasted pattern's header without asking.
+    pub apply_rle_rules: bool,
+    /// When set, one random neighbor count of `rule` is flipped every this
+    /// many generations, using `rng` so a seed replays the same mutations.
+    pub mutation_interval: Option<u64>,
+    /// The rule before the first mutation, for reverting to.
+    unmutated_rule: Option<Rule>,
+    /// The rule the last pasted pattern asked for, while it differs from
+    /// `rule`.
+    offered_rule: Option<Rule>,
+    pub cell_size: Vector2f,
+    /// World units trimmed off each drawn cell, split evenly between its
+    /// sides, so a gap shows between neighbors. 0 draws cells edge to edge.
+    pub cell_padding: f32,
+    /// Dead cells left between copies when `tile_content` repeats the board.
+    pub tile_spacing: usize,
+    /// Draws row 0 at the bottom of the board instead of the top, the way
+    /// a y axis pointing up would. Clicks are mapped to match.
+    pub y_up: bool,
+    pub cell_color: Color,
+    pub locked_color: Color,
+    pub forbidden_color: Color,
+    /// Accent for cells born in the latest generation.
+    pub born_color: Color,
+    /// When set, each live cell takes the color of the pixel at its
+    /// coordinates. Cells past the image's edges use `cell_color`.
+    pub palette: Option<Image>,
+    /// When set, the board's dead cells are filled with this color instead
+    /// of showing the cleared background.
+    pub dead_cell_color: Option<Color>,
+    pub show_help: bool,
+    /// Which page of the help is showing, and how many there were the last
+    /// time it was drawn, which depends on the window's height.
+    pub help_page: usize,
+    pub help_pages: usize,
+    pub text_overlays: bool,
+    /// Per-cell afterimage intensity in `0.0..=1.0`, indexed like `grid`.
+    trail: Vec<f32>,
+    pub show_trail: bool,
+    /// Which cells came alive in the latest generation, indexed like `grid`.
+    /// Only kept up to date while `show_births` is on.
+    born: Vec<bool>,
+    pub show_births: bool,
+    /// How often each cell has changed state lately, indexed like `grid`.
+    /// Every change adds 1, which fades by `activity_decay` each generation
+    /// and is taken back out after `activity_window` generations. Only kept
+    /// up to date while `show_activity` is on.
+    activity: Vec<f32>,
+    /// The cells that changed in each of the last `activity_window`
+    /// generations, oldest first, so their heat can expire.
+    activity_changes: VecDeque<Vec<usize>>,
+    /// Shades the background by `activity`, showing where the board is busy.
+    pub show_activity: bool,
+    /// How many generations a change counts towards the activity map.
+    pub activity_window: usize,
+    /// The fraction of its heat a change keeps each generation, in `(0, 1]`.
+    pub activity_decay: f32,
+    /// Colors each 8-connected cluster of live cells differently.
+    pub show_components: bool,
+    /// The population after each of the latest generations, oldest first.
+    pub population_history: VecDeque<usize>,
+    pub show_population_graph: bool,
+    /// Shows clickable birth and survival checkboxes for editing `rule`.
+    pub show_rule_editor: bool,
+    /// The largest all-dead rectangle as of the last time it was looked
+    /// for, outlined as a place to put new patterns.
+    pub quiet_zone: Option<Selection>,
+    /// Outlines the smallest rectangle holding every live cell.
+    pub show_bounding_box: bool,
+    /// Shows cell indices along the top and left edges of the window.
+    pub show_rulers: bool,
+    /// Writes each cell's coordinates inside it when zoomed in far enough.
+    pub show_cell_labels: bool,
+    /// How much each trail mark fades per generation.
+    pub trail_decay: f32,
+    pub rng: Rng,
+    /// What `rng` was seeded with, so its sequence can be replayed.
+    pub seed: u64,
+    /// Chance that each dead cell is born at random every generation.
+    pub noise_rate: f32,
+    /// A short notice shown on screen, and when it was posted.
+    pub message: Option<(String, Instant)>,
+    /// The cell under the mouse pointer, if it's over the grid.
+    pub hovered_cell: Option<Vector2<usize>>,
+    /// Shows a tooltip explaining the hovered cell's next state.
+    pub show_inspector: bool,
+    pub selection: Option<Selection>,
+    /// A cell picked with Shift+click whose neighborhood is highlighted.
+    pub focused_cell: Option<Vector2<usize>>,
+    /// Highlights the cells `focused_cell` influences rather than the ones
+    /// that influence it.
+    pub show_influenced: bool,
+    /// Cells whose state is recorded every generation, for watching one
+    /// spot while chasing a bug.
+    pub probes: BTreeSet<(usize, usize)>,
+    /// Readings taken since the log was last flushed, oldest first.
+    probe_log: Vec<ProbeReading>,
+    /// Whether the right button is held and still resizing `selection`.
+    selecting: bool,
+    stroke: Option<Stroke>,
+    /// The library oscillator last placed and the generation it was placed
+    /// at, so its phase can be shown until the board is cleared.
+    pub oscillator: Option<(&'static LibraryPattern, u64)>,
+    /// A pattern waiting to be stamped where the board is next clicked.
+    pub active_pattern: Option<BoolGrid2D>,
+    /// Clockwise quarter turns applied to `active_pattern` before stamping.
+    pub pattern_rotation: u8,
+    /// Which mirror images of each cell painted or clicked are set too.
+    pub symmetry: Symmetry,
+    /// The column and row `symmetry` mirrors across, doubled so an axis
+    /// running between two cells is still a whole number. `None` mirrors
+    /// across the middle of the board.
+    pub symmetry_axes: Option<(isize, isize)>,
+    /// The ends of a line being dragged out with Alt held. It's drawn as a
+    /// preview and only set on the board when the button is released.
+    pub line: Option<(Vector2<usize>, Vector2<usize>)>,
+    /// When set, only cells inside this rectangle are simulated and the rest
+    /// of the board stays frozen. Cells on its edge still count their frozen
+    /// neighbors outside.
+    pub active_region: Option<Selection>,
+    /// Shows the rule, boundary and speed in a corner of the window.
+    pub show_status: bool,
+    /// Shows how long each phase of a frame took.
+    pub show_timings: bool,
+    /// How long the latest `update` spent counting neighbors and applying
+    /// the rule, as opposed to bookkeeping such as trails and noise.
+    pub step_time: Time,
+    /// Keeps the view centered on the live cells as they move.
+    pub following: bool,
+    /// A board to measure the current one against, such as a saved run.
+    pub reference: Option<BoolGrid2D>,
+    /// Highlights the cells that differ from `reference`.
+    pub show_differences: bool,
+    /// Boards saved into numbered slots.
+    pub checkpoints: HashMap<u8, Checkpoint>,
+    /// The board simulation last started from, for `rewind`. It's taken at
+    /// the first generation, and taken again at the next one after the
+    /// board is loaded or randomized, or edited while paused.
+    start: Option<Checkpoint>,
+    callbacks: Callbacks,
+    /// Every cell's live neighbor count, indexed like `grid`, filled in the
+    /// first time it's asked for. Single-cell toggles adjust it in place;
+    /// anything else that changes the board throws it away.
+    neighbor_counts: RefCell<Option<Vec<u8>>>,
+    /// The cells the generation in progress has changed, collected only
+    /// while `update_with_delta` is running.
+    delta: Option<Vec<(usize, usize, bool)>>,
+}
+
+impl Game {
+    /// A `width` by `height` board with every other setting at its default.
+    ///
+    /// Panics if the board would be empty or larger than `MAX_BOARD_CELLS`;
+    /// `try_new` reports that as an error instead.
+    pub fn new(width: usize, height: usize) -> Game {
+        match Game::try_new(GameConfig {
+            width,
+            height,
+            ..GameConfig::default()
+        }) {
+            Ok(game) => game,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    /// A board set up from `config`, checking all of it first. The rule
+    /// needs no check here, since a `Rule` can only hold valid counts.
+    pub fn try_new(config: GameConfig) -> Result<Game, GameError> {
+        let GameConfig {
+            width,
+            height,
+            rule,
+            boundary,
+            topology,
+            cell_size,
+            cell_color,
+            dead_cell_color,
+        } = config;
+
+        if width == 0 || height == 0 {
+            return Err(GameError::EmptyBoard { width, height });
+        }
+        if width
+            .checked_mul(height)
+            .is_none_or(|cells| cells > MAX_BOARD_CELLS)
+        {
+            return Err(GameError::BoardTooLarge { width, height });
+        }
+        let valid_length = |length: f32| length.is_finite() && length > 0.0;
+        if !valid_length(cell_size.x) || !valid_length(cell_size.y) {
+            return Err(GameError::InvalidCellSize(cell_size));
+        }
+        if !topology.fits(boundary, height) {
+            return Err(GameError::OddHexagonalHeight { height, boundary });
+        }
+
+        let seed = rng::seed_from_time();
+
+        Ok(Game {
+            grid: BoolGrid2D::new(width, height),
+            simulation_grid: BoolGrid2D::new(width, height),
+            paused: false,
+            sandbox: false,
+            tick_duration: Time::seconds(0.2),
+            max_speed: false,
+            generation: 0,
+            remaining_ticks: None,
+            max_generation: None,
+            cleared_at: 0,
+            total_births: 0,
+            total_deaths: 0,
+            escaped_gliders: 0,
+            count_escaped_gliders: false,
+            locked: BoolGrid2D::new(width, height),
+            forbidden: BoolGrid2D::new(width, height),
+            log_violations: false,
+            boundary,
+            topology,
+            update_mode: UpdateMode::Synchronous,
+            rule,
+            rule_table: None,
+            cell_states: vec![0; width * height],
+            apply_rle_rules: false,
+            mutation_interval: None,
+            unmutated_rule: None,
+            offered_rule: None,
+            cell_size,
+            cell_padding: 0.0,
+            tile_spacing: 1,
+            y_up: false,
+            cell_color,
+            locked_color: Color::rgb(255, 160, 40),
+            forbidden_color: Color::rgb(255, 40, 40),
+            born_color: Color::rgb(120, 255, 140),
+            palette: None,
+            dead_cell_color,
+            show_help: false,
+            help_page: 0,
+            help_pages: 1,
+            text_overlays: false,
+            trail: vec![0.0; width * height],
+            show_trail: false,
+            born: vec![false; width * height],
+            show_births: false,
+            activity: vec![0.0; width * height],
+            activity_changes: VecDeque::new(),
+            show_activity: false,
+            activity_window: 100,
+            activity_decay: 0.95,
+            show_components: false,
+            population_history: VecDeque::with_capacity(POPULATION_HISTORY_LENGTH),
+            show_population_graph: false,
+            quiet_zone: None,
+            show_bounding_box: false,
+            show_rule_editor: false,
+            show_rulers: false,
+            show_cell_labels: false,
+            trail_decay: 0.1,
+            rng: Rng::new(seed),
+            seed,
+            noise_rate: 0.0,
+            message: None,
+            hovered_cell: None,
+            show_inspector: false,
+            selection: None,
+            focused_cell: None,
+            probes: BTreeSet::new(),
+            probe_log: Vec::new(),
+            show_influenced: false,
+            selecting: false,
+            stroke: None,
+            symmetry: Symmetry::Off,
+            symmetry_axes: None,
+            line: None,
+            oscillator: None,
+            active_pattern: None,
+            pattern_rotation: 0,
+            active_region: None,
+            following: false,
+            show_timings: false,
+            show_status: true,
+            step_time: Time::ZERO,
+            reference: None,
+            show_differences: false,
+            checkpoints: HashMap::new(),
+            start: None,
+            callbacks: Callbacks::default(),
+            neighbor_counts: RefCell::new(None),
+            delta: None,
+        })
+    }
+
+    /// The window title, which also shows whether the game is paused or in
+    /// sandbox mode.
+    pub fn title(&self) -> String {
+        let mut title = "Conway's Game of Life".to_string();
+        if self.rule != Rule::CONWAY {
+            title.push_str(&format!(" under {}", self.rule));
+        }
+        if self.sandbox {
+            title.push_str(" (sandbox)");
+        } else if self.paused {
+            title.push_str(" (paused)");
+        }
+        title
+    }
+
+    /// The top-left corner of the cell at `(x, y)` in world coordinates. Each
+    /// axis is scaled by its own cell size, so cells needn't be square.
+    pub fn cell_position(&self, x: usize, y: usize) -> Vector2f {
+        Vector2f::new(
+            (x as f32 + self.topology.row_shift(y)) * self.cell_size.x,
+            self.display_row(y) as f32 * self.cell_size.y,
+        )
+    }
+
+    /// How many rows from the top row `y` is drawn. Flipping is its own
+    /// inverse, so this also turns a drawn row back into a board row.
+    fn display_row(&self, y: usize) -> usize {
+        if self.y_up {
+            self.grid.height - 1 - y
+        } else {
+            y
+        }
+    }
+
+    /// The center of mass of the live cells in world coordinates, or `None`
+    /// if the board is empty.
+    pub fn live_centroid(&self) -> Option<Vector2f> {
+        let (x, mut y) = self.grid.centroid()?;
+        if self.y_up {
+            y = self.grid.height as f32 - 1.0 - y;
+        }
+        Some(Vector2f::new(
+            (x + 0.5) * self.cell_size.x,
+            (y + 0.5) * self.cell_size.y,
+        ))
+    }
+
+    /// The size of the whole board in world coordinates, including the
+    /// half cell hexagonal rows stick out by.
+    pub fn world_size(&self) -> Vector2f {
+        let shift = if self.grid.height > 1 {
+            self.topology.row_shift(1)
+        } else {
+            0.0
+        };
+        Vector2f::new(
+            (self.grid.width as f32 + shift) * self.cell_size.x,
+            self.grid.height as f32 * self.cell_size.y,
+        )
+    }
+
+    /// The cell under a point in world coordinates, or `None` if the point is
+    /// outside the grid.
+    pub fn get_cell_below_position(&self, position: Vector2f) -> Option<Vector2<usize>> {
+        let y = (position.y / self.cell_size.y).floor();
+        if y < 0.0 || y >= self.grid.height as f32 {
+            return None;
+        }
+        let y = self.display_row(y as usize);
+
+        let x = (position.x / self.cell_size.x - self.topology.row_shift(y)).floor();
+        if x < 0.0 || x >= self.grid.width as f32 {
+            return None;
+        }
+
+        Some(Vector2::new(x as usize, y))
+    }
+
+    /// Maps a window pixel through the current view, so letterboxing and
+    /// any other viewport changes are taken into account.
+    fn get_cell_below_pixel(
+        &self,
+        window: &RenderWindow,
+        x: i32,
+        y: i32,
+    ) -> Option<Vector2<usize>> {
+        let mouse_pos = window.map_pixel_to_coords(Vector2i::new(x, y), window.view());
+        self.get_cell_below_position(mouse_pos)
+    }
+
+    pub fn toggle_cell(&mut self, position: Vector2<usize>) {
+        let is_alive = !self.grid.get(position.x, position.y);
+        self.grid.set(position.x, position.y, is_alive);
+        if self.paused {
+            self.start = None;
+        }
+        if !self.neighborhoods_mutual() {
+            *self.neighbor_counts.get_mut() = None;
+            return;
+        }
+
+        let mut cache = self.neighbor_counts.borrow_mut();
+        if let Some(counts) = cache.as_mut() {
+            for (nx, ny) in self.neighbors(position.x, position.y) {
+                let count = &mut counts[self.grid.get_index(nx, ny)];
+                *count = if is_alive { *count + 1 } else { *count - 1 };
+            }
+            debug_assert!(
+                *counts == self.count_all_neighbors(),
+                "incremental neighbor counts drifted from a full recount"
+            );
+        }
+    }
+
+    pub fn set_cell(&mut self, position: Vector2<usize>, alive: bool) {
+        if self.grid.get(position.x, position.y) != alive {
+            self.toggle_cell(position);
+        }
+    }
+
+    /// `set_cell` for coordinates that may be off the board, which are left
+    /// alone. Returns whether the cell was on the board.
+    pub fn try_set_cell(&mut self, x: isize, y: isize, alive: bool) -> bool {
+        let (Ok(x), Ok(y)) = (usize::try_from(x), usize::try_from(y)) else {
+            return false;
+        };
+        if x >= self.grid.width || y >= self.grid.height {
+            return false;
+        }
+        self.set_cell(Vector2::new(x, y), alive);
+        true
+    }
+
+    /// The mirror images of `cell` that `symmetry` paints along with it,
+    /// leaving out any that fall off the board or on `cell` itself.
+    fn mirrored_cells(&self, cell: Vector2<usize>) -> Vec<Vector2<usize>> {
+        let (axis_x, axis_y) = self
+            .symmetry_axes
+            .unwrap_or((self.grid.width as isize - 1, self.grid.height as isize - 1));
+        let (x, y) = (cell.x as isize, cell.y as isize);
+        let (flipped_x, flipped_y) = (axis_x - x, axis_y - y);
+        let images = match self.symmetry {
+            Symmetry::Off => vec![],
+            Symmetry::LeftRight => vec![(flipped_x, y)],
+            Symmetry::TopBottom => vec![(x, flipped_y)],
+            Symmetry::FourFold => vec![(flipped_x, y), (x, flipped_y), (flipped_x, flipped_y)],
+        };
+
+        let mut cells: Vec<Vector2<usize>> = Vec::new();
+        for (x, y) in images {
+            let (Ok(x), Ok(y)) = (usize::try_from(x), usize::try_from(y)) else {
+                continue;
+            };
+            let image = Vector2::new(x, y);
+            if x < self.grid.width
+                && y < self.grid.height
+                && image != cell
+                && !cells.contains(&image)
+            {
+                cells.push(image);
+            }
+        }
+        cells
+    }
+
+    /// Sets a cell edited with the mouse, and its mirror images with it.
+    fn paint_cell(&mut self, cell: Vector2<usize>, alive: bool) {
+        self.set_cell(cell, alive);
+        for image in self.mirrored_cells(cell) {
+            self.set_cell(image, alive);
+        }
+    }
+
+    /// Whether a mouse drag is setting cells right now.
+    pub fn is_painting(&self) -> bool {
+        self.stroke.is_some_and(|stroke| stroke.moved)
+    }
+
+    /// Paints from the stroke's last cell to `cell_pos`, along a line so
+    /// fast drags don't leave gaps. It runs for every `MouseMoved` event,
+    /// however many arrive in a frame, so the frame rate can't cause gaps.
+    fn continue_stroke(&mut self, cell_pos: Vector2<usize>) {
+        let Some(mut stroke) = self.stroke else {
+            return;
+        };
+        if stroke.last == cell_pos {
+            return;
+        }
+
+        if !stroke.moved {
+            self.paint_cell(stroke.last, stroke.alive);
+            stroke.moved = true;
+        }
+        let (from, to) = (stroke.last, cell_pos);
+        for (x, y) in line_points(
+            from.x as isize,
+            from.y as isize,
+            to.x as isize,
+            to.y as isize,
+        ) {
+            self.paint_cell(Vector2::new(x as usize, y as usize), stroke.alive);
+        }
+        stroke.last = cell_pos;
+        self.stroke = Some(stroke);
+    }
+
+    /// Ends the stroke. One that never left its first cell was a click,
+    /// which toggles a cell with the left button and kills it with the right.
+    fn finish_stroke(&mut self) {
+        match self.stroke.take() {
+            Some(Stroke {
+                alive: true,
+                last,
+                moved: false,
+            }) => self.paint_cell(last, !self.grid.get(last.x, last.y)),
+            Some(Stroke {
+                alive: false,
+                last,
+                moved: false,
+            }) => self.paint_cell(last, false),
+            _ => (),
+        }
+    }
+
+    /// Forgets the cached neighbor counts. Call this after changing `grid`
+    /// or `boundary` directly; `Game`'s own methods already do. While
+    /// paused, this also counts as an edit, making the board the new
+    /// starting point for `rewind`.
+    pub fn invalidate_neighbor_counts(&mut self) {
+        *self.neighbor_counts.get_mut() = None;
+        if self.paused {
+            self.start = None;
+        }
+    }
+
+    fn count_all_neighbors(&self) -> Vec<u8> {
+        self.grid
+            .cells()
+            .map(|(x, y, _)| self.get_neighbors_count(x, y) as u8)
+            .collect()
+    }
+
+    /// Like `get_neighbors_count`, but read from a cache that survives
+    /// single-cell edits, which keeps overlays cheap while drawing.
+    pub fn cached_neighbor_count(&self, x: usize, y: usize) -> i32 {
+        let mut cache = self.neighbor_counts.borrow_mut();
+        let counts = cache.get_or_insert_with(|| self.count_all_neighbors());
+        counts[self.grid.get_index(x, y)] as i32
+    }
+
+    /// Calls `callback` after every generation `update` simulates.
+    pub fn on_generation(&mut self, callback: Callback) {
+        self.callbacks.generation.push(callback);
+    }
+
+    /// Calls `callback` after all or part of the board is cleared.
+    pub fn on_clear(&mut self, callback: Callback) {
+        self.callbacks.clear.push(callback);
+    }
+
+    /// Calls `callback` after a generation that left the board unchanged,
+    /// which happens once it settles into still lifes.
+    pub fn on_stable(&mut self, callback: Callback) {
+        self.callbacks.stable.push(callback);
+    }
+
+    /// Runs the callbacks `pick` chooses. They're taken out of `self` while
+    /// they run so that each can be handed the whole game.
+    fn run_callbacks(&mut self, pick: fn(&mut Callbacks) -> &mut Vec<Callback>) {
+        let mut callbacks = std::mem::take(pick(&mut self.callbacks));
+        for callback in &mut callbacks {
+            callback(self);
+        }
+        // Keep any callbacks registered while these were running.
+        callbacks.append(pick(&mut self.callbacks));
+        *pick(&mut self.callbacks) = callbacks;
+    }
+
+    /// Kills every cell on the board.
+    pub fn clear(&mut self) {
+        self.grid.array.iter_mut().for_each(|cell| *cell = false);
+        self.invalidate_neighbor_counts();
+        self.cleared_at = self.generation;
+        self.total_births = 0;
+        self.total_deaths = 0;
+        self.escaped_gliders = 0;
+        self.oscillator = None;
+        self.run_callbacks(|callbacks| &mut callbacks.clear);
+    }
+
+    /// Kills every cell in the rectangle spanned by the two corners, in any
+    /// order. Parts of the rectangle outside the grid are ignored.
+    pub fn clear_region(&mut self, x0: usize, y0: usize, x1: usize, y1: usize) {
+        let (left, right) = (x0.min(x1), x0.max(x1).min(self.grid.width - 1));
+        let (top, bottom) = (y0.min(y1), y0.max(y1).min(self.grid.height - 1));
+
+        for y in top..=bottom {
+            for x in left..=right {
+                self.grid.set(x, y, false);
+            }
+        }
+        self.invalidate_neighbor_counts();
+        self.run_callbacks(|callbacks| &mut callbacks.clear);
+    }
+
+    /// Flips every cell in the rectangle spanned by the two corners, which
+    /// must be on the grid.
+    pub fn invert_region(&mut self, x0: usize, y0: usize, x1: usize, y1: usize) {
+        for y in y0.min(y1)..=y0.max(y1) {
+            for x in x0.min(x1)..=x0.max(x1) {
+                self.grid.set(x, y, !self.grid.get(x, y));
+            }
+        }
+        self.invalidate_neighbor_counts();
+    }
+
+    /// The cells `view` shows at least part of, clamped to the grid, or
+    /// `None` if it's looking past the board entirely.
+    pub fn visible_cells(&self, view: &View) -> Option<Selection> {
+        let (center, size) = (view.center(), view.size());
+        let left = (center.x - size.x / 2.0) / self.cell_size.x;
+        let top = (center.y - size.y / 2.0) / self.cell_size.y;
+        let right = (center.x + size.x / 2.0) / self.cell_size.x;
+        let bottom = (center.y + size.y / 2.0) / self.cell_size.y;
+
+        let (width, height) = (self.grid.width as f32, self.grid.height as f32);
+        if right <= 0.0 || bottom <= 0.0 || left >= width || top >= height {
+            return None;
+        }
+
+        // Casting saturates, so the edges only need clamping from above.
+        let cell = |x: f32, y: f32| {
+            Vector2::new(
+                (x as usize).min(self.grid.width - 1),
+                self.display_row((y as usize).min(self.grid.height - 1)),
+            )
+        };
+        Some(Selection {
+            start: cell(left, top),
+            end: cell(right.ceil() - 1.0, bottom.ceil() - 1.0),
+        })
+    }
+
+    /// Whether any live cell is among the ones `view` shows.
+    pub fn view_has_live_cells(&self, view: &View) -> bool {
+        let Some(visible) = self.visible_cells(view) else {
+            return false;
+        };
+        let (top_left, bottom_right) = visible.corners();
+        (top_left.y..=bottom_right.y)
+            .any(|y| (top_left.x..=bottom_right.x).any(|x| self.grid.get(x, y)))
+    }
+
+    /// Inverts or clears whatever part of the board `view` shows.
+    fn edit_visible(&mut self, action: Action, view: &View) {
+        let Some(Selection { start, end }) = self.visible_cells(view) else {
+            self.show_message("No cells in view".to_string());
+            return;
+        };
+
+        if action == Action::InvertVisible {
+            self.invert_region(start.x, start.y, end.x, end.y);
+        } else {
+            self.clear_region(start.x, start.y, end.x, end.y);
+        }
+    }
+
+    /// Marks the selection forbidden, or unmarks it if all of it already is.
+    fn toggle_forbidden(&mut self) {
+        let Some(selection) = self.selection else {
+            self.show_message("Select a region first".to_string());
+            return;
+        };
+
+        let (top_left, bottom_right) = selection.corners();
+        let cells = (top_left.y..=bottom_right.y)
+            .flat_map(|y| (top_left.x..=bottom_right.x).map(move |x| (x, y)));
+        let forbid = !cells.clone().all(|(x, y)| self.forbidden.get(x, y));
+        for (x, y) in cells {
+            self.forbidden.set(x, y, forbid);
+        }
+
+        let size = selection.size();
+        self.show_message(format!(
+            "{} the {}x{} selection",
+            if forbid { "Forbade" } else { "Allowed" },
+            size.x,
+            size.y
+        ));
+    }
+
+    /// How many live cells are in forbidden cells.
+    pub fn violations(&self) -> usize {
+        self.grid
+            .live_cells()
+            .filter(|&(x, y)| self.forbidden.get(x, y))
+            .count()
+    }
+
+    /// Adds `margin` dead cells on every side of the board. Everything tied
+    /// to cell coordinates moves along with the cells; trails, birth
+    /// highlights and the activity map start over.
+    pub fn grow(&mut self, margin: usize) {
+        let shift = |cell: Vector2<usize>| Vector2::new(cell.x + margin, cell.y + margin);
+        let shift_region = |region: Selection| Selection {
+            start: shift(region.start),
+            end: shift(region.end),
+        };
+
+        self.grid = self.grid.grow(margin);
+        self.invalidate_neighbor_counts();
+        self.locked = self.locked.grow(margin);
+        self.forbidden = self.forbidden.grow(margin);
+        self.simulation_grid = BoolGrid2D::new(self.grid.width, self.grid.height);
+        self.trail = vec![0.0; self.grid.array.len()];
+        self.born = vec![false; self.grid.array.len()];
+        self.reset_activity();
+        for checkpoint in self.checkpoints.values_mut().chain(self.start.as_mut()) {
+            checkpoint.grid = checkpoint.grid.grow(margin);
+        }
+        self.reference = self
+            .reference
+            .as_ref()
+            .map(|reference| reference.grow(margin));
+
+        self.selection = self.selection.map(shift_region);
+        self.active_region = self.active_region.map(shift_region);
+        self.quiet_zone = self.quiet_zone.map(shift_region);
+        let doubled_margin = 2 * margin as isize;
+        self.symmetry_axes = self
+            .symmetry_axes
+            .map(|(x, y)| (x + doubled_margin, y + doubled_margin));
+        self.focused_cell = self.focused_cell.map(shift);
+        self.hovered_cell = self.hovered_cell.map(shift);
+        self.probes = self
+            .probes
+            .iter()
+            .map(|&(x, y)| (x + margin, y + margin))
+            .collect();
+
+        self.show_message(format!(
+            "Board is now {}x{}",
+            self.grid.width, self.grid.height
+        ));
+    }
+
+    /// Makes the board `width` by `height`, keeping the cells that still fit
+    /// where `anchor` puts them. Coordinates that move off the board, such
+    /// as a probe or a selection corner, are dropped; trails, birth
+    /// highlights and the activity map start over.
+    ///
+    /// Panics if either size is 0.
+    pub fn resize(&mut self, width: usize, height: usize, anchor: Anchor) {
+        assert!(width > 0 && height > 0, "a board needs at least one cell");
+        let (dx, dy) = self.grid.resize_offset(width, height, anchor);
+        let shift = |cell: Vector2<usize>| -> Option<Vector2<usize>> {
+            let x = usize::try_from(cell.x as isize + dx).ok()?;
+            let y = usize::try_from(cell.y as isize + dy).ok()?;
+            (x < width && y < height).then(|| Vector2::new(x, y))
+        };
+        let shift_region = |region: Selection| -> Option<Selection> {
+            Some(Selection {
+                start: shift(region.start)?,
+                end: shift(region.end)?,
+            })
+        };
+
+        self.grid = self.grid.resize(width, height, anchor);
+        self.invalidate_neighbor_counts();
+        self.locked = self.locked.resize(width, height, anchor);
+        self.forbidden = self.forbidden.resize(width, height, anchor);
+        self.simulation_grid = BoolGrid2D::new(width, height);
+        self.trail = vec![0.0; self.grid.array.len()];
+        self.born = vec![false; self.grid.array.len()];
+        self.reset_activity();
+        for checkpoint in self.checkpoints.values_mut().chain(self.start.as_mut()) {
+            checkpoint.grid = checkpoint.grid.resize(width, height, anchor);
+        }
+        self.reference = self
+            .reference
+            .as_ref()
+            .map(|reference| reference.resize(width, height, anchor));
+
+        self.selection = self.selection.and_then(shift_region);
+        self.active_region = self.active_region.and_then(shift_region);
+        self.quiet_zone = self.quiet_zone.and_then(shift_region);
+        self.symmetry_axes = self.symmetry_axes.map(|(x, y)| (x + 2 * dx, y + 2 * dy));
+        self.focused_cell = self.focused_cell.and_then(shift);
+        self.hovered_cell = self.hovered_cell.and_then(shift);
+        self.probes = self
+            .probes
+            .iter()
+            .filter_map(|&(x, y)| shift(Vector2::new(x, y)))
+            .map(|cell| (cell.x, cell.y))
+            .collect();
+
+        if self.topology.fits(self.boundary, height) {
+            self.show_message(format!("Board is now {}x{}", width, height));
+        } else {
+            self.boundary = Boundary::Dead;
+            *self.neighbor_counts.get_mut() = None;
+            self.show_message(format!(
+                "Board is now {}x{}, with dead edges since odd hexagonal rows can't wrap",
+                width, height
+            ));
+        }
+    }
+
+    /// Outlines the largest empty rectangle on the board, or hides the
+    /// outline if one is already shown.
+    fn toggle_quiet_zone(&mut self) {
+        if self.quiet_zone.take().is_some() {
+            return;
+        }
+        match self.grid.largest_empty_rectangle() {
+            Some(((left, top), (right, bottom))) => {
+                let zone = Selection {
+                    start: Vector2::new(left, top),
+                    end: Vector2::new(right, bottom),
+                };
+                let size = zone.size();
+                self.quiet_zone = Some(zone);
+                self.show_message(format!(
+                    "Largest empty area: {}x{} cells from {},{}",
+                    size.x, size.y, left, top
+                ));
+            }
+            None => self.show_message("Every cell is alive".to_string()),
+        }
+    }
+
+    /// Takes `GROW_MARGIN` cells off every side, as long as some board is
+    /// left.
+    fn shrink(&mut self) {
+        let (width, height) = (self.grid.width, self.grid.height);
+        if width <= 2 * GROW_MARGIN || height <= 2 * GROW_MARGIN {
+            self.show_message(format!(
+                "A {}x{} board is too small to shrink",
+                width, height
+            ));
+            return;
+        }
+        self.resize(
+            width - 2 * GROW_MARGIN,
+            height - 2 * GROW_MARGIN,
+            Anchor::Center,
+        );
+    }
+
+    /// `active_pattern` as it will be stamped, rotated, and the cell its
+    /// top-left corner lands on when centered on the hovered cell.
+    pub fn placement(&self) -> Option<(BoolGrid2D, isize, isize)> {
+        let pattern = self.active_pattern.as_ref()?.rotated(self.pattern_rotation);
+        let cell = self.hovered_cell?;
+        let x = cell.x as isize - pattern.width() as isize / 2;
+        let y = cell.y as isize - pattern.height() as isize / 2;
+        Some((pattern, x, y))
+    }
+
+    /// Sets every cell on the straight line between the two points to `val`.
+    /// The points may lie off the board; only the part of the line on it is
+    /// drawn.
+    pub fn draw_line(&mut self, x0: isize, y0: isize, x1: isize, y1: isize, val: bool) {
+        for (x, y) in line_points(x0, y0, x1, y1) {
+            if (0..self.grid.width as isize).contains(&x)
+                && (0..self.grid.height as isize).contains(&y)
+            {
+                self.grid.set(x as usize, y as usize, val);
+            }
+        }
+        self.invalidate_neighbor_counts();
+    }
+
+    /// Adds the board's mirror image along `axis` to itself, making it
+    /// symmetric.
+    pub fn overlay_mirrored(&mut self, axis: Axis) {
+        let mirrored = self.grid.flipped(axis);
+        self.grid.union_with(&mirrored);
+        self.invalidate_neighbor_counts();
+    }
+
+    /// Repeats the live cells' bounding box across the whole board like
+    /// wallpaper, `tile_spacing` dead cells apart, keeping the original where
+    /// it is. Copies that don't fit at the edges are cut off.
+    pub fn tile_content(&mut self) {
+        let (Some(tile), Some(((left, top), _))) =
+            (self.grid.crop_to_content(), self.grid.content_bounds())
+        else {
+            self.show_message("Board is empty, nothing to tile".to_string());
+            return;
+        };
+
+        // The first copy along each axis, which may hang off the near edge.
+        let first = |origin: usize, stride: usize| -> isize {
+            let offset = (origin % stride) as isize;
+            if offset > 0 {
+                offset - stride as isize
+            } else {
+                0
+            }
+        };
+        let stride_x = tile.width() + self.tile_spacing;
+        let stride_y = tile.height() + self.tile_spacing;
+
+        let mut copies = 0;
+        let mut y = first(top, stride_y);
+        while y < self.grid.height as isize {
+            let mut x = first(left, stride_x);
+            while x < self.grid.width as isize {
+                self.grid.stamp(&tile, x, y);
+                copies += 1;
+                x += stride_x as isize;
+            }
+            y += stride_y as isize;
+        }
+        self.invalidate_neighbor_counts();
+
+        self.show_message(format!(
+            "Tiled a {}x{} pattern {} times",
+            tile.width(),
+            tile.height(),
+            copies
+        ));
+    }
+
+    /// Sets the live cells of `pattern` on the board with its top-left corner
+    /// at `(x, y)`. Cells that would land outside the grid are dropped.
+    pub fn stamp_pattern(&mut self, pattern: &BoolGrid2D, x: isize, y: isize) {
+        self.grid.stamp(pattern, x, y);
+        self.invalidate_neighbor_counts();
+    }
+
+    /// Stamps every `(pattern, x, y)` as `stamp_pattern` would, and returns
+    /// the cells more than one of them set alive, in row-major order.
+    /// Overlaps are allowed, but when building a machine out of parts they
+    /// usually mean one was placed wrong.
+    pub fn stamp_patterns(
+        &mut self,
+        stamps: &[(&BoolGrid2D, isize, isize)],
+    ) -> Vec<Vector2<usize>> {
+        let (width, height) = (self.grid.width, self.grid.height);
+        let mut claimed = BoolGrid2D::new(width, height);
+        let mut conflicts = BoolGrid2D::new(width, height);
+
+        for &(pattern, x, y) in stamps {
+            let mut footprint = BoolGrid2D::new(width, height);
+            footprint.stamp(pattern, x, y);
+            for (cx, cy) in footprint.live_cells() {
+                if claimed.get(cx, cy) {
+                    conflicts.set(cx, cy, true);
+                } else {
+                    claimed.set(cx, cy, true);
+                }
+            }
+        }
+
+        self.grid.union_with(&claimed);
+        self.invalidate_neighbor_counts();
+        conflicts
+            .live_cells()
+            .map(|(x, y)| Vector2::new(x, y))
+            .collect()
+    }
+
+    /// Where `mode` puts the top-left corner of `pattern`. `position` is the
+    /// corner its file asks for, used by `Absolute` and treated as `(0, 0)`
+    /// when missing.
+    pub fn placement_offset(
+        &self,
+        pattern: &BoolGrid2D,
+        position: Option<(isize, isize)>,
+        mode: PlacementMode,
+    ) -> (isize, isize) {
+        match mode {
+            PlacementMode::Center => (
+                (self.grid.width as isize - pattern.width() as isize) / 2,
+                (self.grid.height as isize - pattern.height() as isize) / 2,
+            ),
+            PlacementMode::TopLeft => (0, 0),
+            PlacementMode::Absolute => position.unwrap_or((0, 0)),
+        }
+    }
+
+    /// Stamps `pattern` where `mode` says, as `placement_offset` works out.
+    /// Cells that land off the board are dropped.
+    pub fn load_pattern(
+        &mut self,
+        pattern: &BoolGrid2D,
+        position: Option<(isize, isize)>,
+        mode: PlacementMode,
+    ) {
+        let (x, y) = self.placement_offset(pattern, position, mode);
+        self.stamp_pattern(pattern, x, y);
+        self.start = None;
+    }
+
+    /// Places a library pattern like `load_pattern`. Oscillators start
+    /// having their phase tracked from this generation.
+    pub fn load_library_pattern(&mut self, pattern: &'static LibraryPattern, mode: PlacementMode) {
+        self.load_pattern(&pattern.grid(), None, mode);
+        if pattern.period.is_some() {
+            self.oscillator = Some((pattern, self.generation));
+        }
+    }
+
+    /// The tracked oscillator's current phase, like "pulsar phase 1/3".
+    pub fn oscillator_phase(&self) -> Option<String> {
+        let (pattern, placed_at) = self.oscillator?;
+        pattern.phase_label(self.generation - placed_at)
+    }
+
+    /// Lays `pattern` out on an empty board-sized buffer where `mode` says,
+    /// then combines the board with that buffer cell by cell. With `AndNot`
+    /// this erases the pattern's shape, with `And` it masks the board to it.
+    pub fn combine_pattern(
+        &mut self,
+        pattern: &BoolGrid2D,
+        position: Option<(isize, isize)>,
+        mode: PlacementMode,
+        op: BoolOp,
+    ) {
+        let (x, y) = self.placement_offset(pattern, position, mode);
+        let mut buffer = BoolGrid2D::new(self.grid.width, self.grid.height);
+        buffer.stamp(pattern, x, y);
+        self.grid.combine(&buffer, op);
+        self.invalidate_neighbor_counts();
+    }
+
+    /// Tells the user something, on screen if text can be drawn and on stdout
+    /// otherwise.
+    pub fn show_message(&mut self, text: String) {
+        if self.text_overlays {
+            self.message = Some((text, Instant::now()));
+        } else {
+            println!("{}", text);
+        }
+    }
+
+    pub fn save_checkpoint(&mut self, slot: u8) {
+        let checkpoint = Checkpoint {
+            grid: self.grid.clone(),
+            generation: self.generation,
+        };
+        self.checkpoints.insert(slot, checkpoint);
+        self.show_message(format!(
+            "Saved checkpoint {} at generation {}",
+            slot, self.generation
+        ));
+    }
+
+    /// Puts back the board and generation saved in `slot`. Locked cells are
+    /// left alone since they aren't part of a checkpoint.
+    pub fn restore_checkpoint(&mut self, slot: u8) {
+        let Some(checkpoint) = self.checkpoints.get(&slot) else {
+            self.show_message(format!("Checkpoint {} is empty", slot));
+            return;
+        };
+
+        self.grid = checkpoint.grid.clone();
+        self.generation = checkpoint.generation;
+        self.invalidate_neighbor_counts();
+        self.born.iter_mut().for_each(|born| *born = false);
+        self.trail.iter_mut().for_each(|mark| *mark = 0.0);
+        self.show_message(format!(
+            "Restored checkpoint {} at generation {}",
+            slot, self.generation
+        ));
+    }
+
+    /// Puts back the board simulation last started from, as generation 0,
+    /// however many generations have passed since.
+    pub fn rewind(&mut self) {
+        let Some(start) = self.start.take() else {
+            self.show_message("Already at the start".to_string());
+            return;
+        };
+
+        let elapsed = self.generation.saturating_sub(start.generation);
+        self.grid = start.grid.clone();
+        self.generation = 0;
+        self.cleared_at = 0;
+        self.total_births = 0;
+        self.total_deaths = 0;
+        self.escaped_gliders = 0;
+        self.invalidate_neighbor_counts();
+        self.born.iter_mut().for_each(|born| *born = false);
+        self.trail.iter_mut().for_each(|mark| *mark = 0.0);
+        self.reset_activity();
+        // An oscillator placed right at the start keeps its phase; one
+        // placed earlier can't be lined up with the new generation count.
+        self.oscillator = self
+            .oscillator
+            .filter(|&(_, placed_at)| placed_at == start.generation)
+            .map(|(pattern, _)| (pattern, 0));
+        self.show_message(format!("Rewound {} generations to the start", elapsed));
+        self.start = Some(start);
+    }
+
+    /// Makes `pattern` the reference board, placed at the top-left corner
+    /// and cut or padded to the board's size.
+    pub fn set_reference(&mut self, pattern: &BoolGrid2D) {
+        let mut reference = BoolGrid2D::new(self.grid.width, self.grid.height);
+        for (x, y) in pattern.live_cells() {
+            if x < reference.width && y < reference.height {
+                reference.set(x, y, true);
+            }
+        }
+        self.reference = Some(reference);
+    }
+
+    /// The reference board, if it's still the same size as the board.
+    fn sized_reference(&self) -> Option<&BoolGrid2D> {
+        self.reference.as_ref().filter(|reference| {
+            (reference.width, reference.height) == (self.grid.width, self.grid.height)
+        })
+    }
+
+    fn report_difference(&mut self) {
+        let text = match self.sized_reference() {
+            Some(reference) => format!(
+                "{} cells differ from the reference",
+                self.grid.hamming_distance(reference)
+            ),
+            None => "No reference board to compare with".to_string(),
+        };
+        self.show_message(text);
+    }
+
+    /// Writes the live cells' bounding box to an RLE file in the working
+    /// directory, named after the generation.
+    fn save_crop(&mut self) {
+        let Some(pattern) = self.grid.crop_to_content() else {
+            self.show_message("Board is empty, nothing to save".to_string());
+            return;
+        };
+
+        let path = format!("pattern-gen{}.rle", self.generation);
+        let text = match fs::write(&path, rle::to_rle(&pattern, self.rule)) {
+            Ok(()) => format!(
+                "Saved {}x{} pattern to {}",
+                pattern.width(),
+                pattern.height(),
+                path
+            ),
+            Err(error) => format!("Couldn't save {}: {}", path, error),
+        };
+        self.show_message(text);
+    }
+
+    /// Readings taken since the log was last flushed, oldest first.
+    pub fn probe_log(&self) -> &[ProbeReading] {
+        &self.probe_log
+    }
+
+    /// Appends the buffered readings to `path`, one `generation x,y state`
+    /// line each, and empties the buffer. Returns how many were written.
+    pub fn flush_probe_log(&mut self, path: &Path) -> io::Result<usize> {
+        let mut text = String::new();
+        for reading in &self.probe_log {
+            let state = if reading.alive { "alive" } else { "dead" };
+            text += &format!(
+                "{} {},{} {}\n",
+                reading.generation, reading.x, reading.y, state
+            );
+        }
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?
+            .write_all(text.as_bytes())?;
+        Ok(std::mem::take(&mut self.probe_log).len())
+    }
+
+    /// Starts or stops probing the hovered cell.
+    fn toggle_probe(&mut self) {
+        let Some(cell) = self.hovered_cell else {
+            self.show_message("Point at a cell to probe it".to_string());
+            return;
+        };
+
+        let text = if self.probes.insert((cell.x, cell.y)) {
+            format!("Probing {},{}", cell.x, cell.y)
+        } else {
+            self.probes.remove(&(cell.x, cell.y));
+            format!("Stopped probing {},{}", cell.x, cell.y)
+        };
+        self.show_message(text);
+    }
+
+    fn flush_probes(&mut self) {
+        let text = match self.flush_probe_log(Path::new(PROBE_LOG_PATH)) {
+            Ok(written) => format!("Wrote {} probe readings to {}", written, PROBE_LOG_PATH),
+            Err(error) => format!("Couldn't write {}: {}", PROBE_LOG_PATH, error),
+        };
+        self.show_message(text);
+    }
+
+    /// Writes the live cells' coordinates to `board-gen<N>.csv`.
+    fn save_csv(&mut self) {
+        let path = format!("board-gen{}.csv", self.generation);
+        let text = match fs::write(&path, self.grid.to_csv()) {
+            Ok(()) => format!("Saved {} live cells to {}", self.grid.population(), path),
+            Err(error) => format!("Couldn't save {}: {}", path, error),
+        };
+        self.show_message(text);
+    }
+
+    fn paste_rle(&mut self) {
+        let text = clipboard::get_string().to_rust_string();
+        if text.trim().is_empty() {
+            self.show_message("Clipboard is empty".to_string());
+            return;
+        }
+
+        match rle::from_rle_with_rule(&text) {
+            Ok((pattern, rule)) => {
+                let mut text = format!(
+                    "Picked up {}x{} pattern, click to place it",
+                    pattern.width(),
+                    pattern.height()
+                );
+                if let Some(note) = rule.and_then(|rule| self.offer_rule(rule)) {
+                    text.push_str(&format!(". {}", note));
+                }
+
+                self.show_message(text);
+                self.active_pattern = Some(pattern);
+                self.pattern_rotation = 0;
+            }
+            Err(error) => self.show_message(format!("Couldn't paste RLE: {}", error)),
+        }
+    }
+
+    /// Deals with a loaded pattern asking for `rule`: switches to it with
+    /// `apply_rle_rules`, or remembers it for Ctrl+R. Returns a note saying
+    /// which, or `None` if it's the rule already in use.
+    pub fn offer_rule(&mut self, rule: Rule) -> Option<String> {
+        if rule == self.rule {
+            self.offered_rule = None;
+            return None;
+        }
+
+        if self.apply_rle_rules {
+            self.set_rule(rule);
+            Some(format!("Switched to its rule, {}", rule))
+        } else {
+            self.offered_rule = Some(rule);
+            Some(format!("It's for {}, Ctrl+R switches to it", rule))
+        }
+    }
+
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+        self.offered_rule = None;
+    }
+
+    /// Flips whether `count` neighbors bring a cell to life or, with
+    /// `survival`, keep it alive, from the rule editor.
+    fn toggle_rule_count(&mut self, survival: bool, count: usize) {
+        let index = if survival { count + 9 } else { count };
+        self.set_rule(self.rule.with_flipped(index));
+        self.show_message(self.rule.describe());
+    }
+
+    /// Flips one random bit of the rule and logs it on stdout.
+    fn mutate_rule(&mut self) {
+        let index = (self.rng.next_u64() % 18) as usize;
+        let mutated = self.rule.with_flipped(index);
+        let (kind, count, now_set) = if index < 9 {
+            ("birth", index, mutated.birth[index])
+        } else {
+            ("survival", index - 9, mutated.survival[index - 9])
+        };
+        let change = if now_set { "added" } else { "removed" };
+        println!(
+            "Generation {}: mutated {} to {} ({} on {} {})",
+            self.generation, self.rule, mutated, kind, count, change
+        );
+
+        self.unmutated_rule.get_or_insert(self.rule);
+        self.rule = mutated;
+    }
+
+    /// Goes back to the rule from before any mutations.
+    fn revert_mutations(&mut self) {
+        match self.unmutated_rule.take() {
+            Some(rule) => {
+                self.rule = rule;
+                self.show_message(format!("Reverted to {}", rule));
+            }
+            None => self.show_message("The rule hasn't mutated".to_string()),
+        }
+    }
+
+    pub fn toggle_lock(&mut self, position: Vector2<usize>) {
+        self.locked.set(
+            position.x,
+            position.y,
+            !self.locked.get(position.x, position.y),
+        );
+    }
+
+    /// The up to 8 cells around `(x, y)`, following the boundary. Cells
+    /// beyond a dead edge are left out.
+    pub fn neighbors(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let (width, height) = (self.grid.width, self.grid.height);
+        self.topology
+            .neighbor_offsets(y)
+            .iter()
+            .filter_map(move |&(dx, dy)| self.boundary.offset(x, y, dx, dy, width, height))
+    }
+
+    /// Whether every cell is sure to be a neighbor of each of its
+    /// neighbors, which patching the cached counts relies on. Offset
+    /// hexagonal rows line up across a torus seam only when it joins an
+    /// odd row to an even one, and mirroring seams can pair them wrongly.
+    fn neighborhoods_mutual(&self) -> bool {
+        match (self.topology, self.boundary) {
+            (GridTopology::Square, _) | (GridTopology::Hexagonal, Boundary::Dead) => true,
+            (GridTopology::Hexagonal, Boundary::Toroidal) => self.grid.height.is_multiple_of(2),
+            (GridTopology::Hexagonal, _) => false,
+        }
+    }
+
+    pub fn get_neighbors_count(&self, x: usize, y: usize) -> i32 {
+        self.neighbors(x, y)
+            .filter(|&(nx, ny)| self.grid.get(nx, ny))
+            .count() as i32
+    }
+
+    /// The cells the highlight around `focused_cell` covers: the neighbors
+    /// that decide its next state, or with `show_influenced` the cells whose
+    /// next state it helps decide.
+    pub fn influence_region(&self) -> Vec<(usize, usize)> {
+        let Some(focus) = self.focused_cell else {
+            return Vec::new();
+        };
+
+        // When neighborhoods are mutual, the cells a cell influences are just
+        // its neighbors. Only the twisted hexagonal seams need the full scan.
+        if !self.show_influenced || self.neighborhoods_mutual() {
+            return self.neighbors(focus.x, focus.y).collect();
+        }
+
+        self.grid
+            .cells()
+            .map(|(x, y, _)| (x, y))
+            .filter(|&(x, y)| {
+                self.neighbors(x, y)
+                    .any(|neighbor| neighbor == (focus.x, focus.y))
+            })
+            .collect()
+    }
+
+    /// The generation after `grid` under this game's rule, boundary and
+    /// locked cells, worked out without touching the board. `grid` must be
+    /// the board's size.
+    pub fn successor(&self, grid: &BoolGrid2D) -> BoolGrid2D {
+        let mut next = BoolGrid2D::new(grid.width, grid.height);
+        for (x, y, alive) in grid.cells() {
+            let state = if self.locked.get(x, y) {
+                alive
+            } else {
+                let count = self
+                    .neighbors(x, y)
+                    .filter(|&(nx, ny)| grid.get(nx, ny))
+                    .count();
+                self.rule.next_state(alive, count as i32)
+            };
+            next.set(x, y, state);
+        }
+        next
+    }
+
+    /// Kills every live cell in a still life or an oscillator, leaving only
+    /// what moves, such as a gun's gliders. Returns how many cells died.
+    pub fn clear_ash(&mut self) -> usize {
+        let mut cleared = 0;
+        for (index, motion) in ash::classify(self).into_iter().enumerate() {
+            if matches!(motion, Some(CellMotion::Still | CellMotion::Oscillating)) {
+                self.grid.array[index] = false;
+                cleared += 1;
+            }
+        }
+        self.invalidate_neighbor_counts();
+        cleared
+    }
+
+    /// What the cell at `(x, y)` will be in the next generation.
+    pub fn next_cell_state(&self, x: usize, y: usize) -> bool {
+        self.next_state_with(x, y, self.get_neighbors_count(x, y))
+    }
+
+    /// `next_cell_state` using the cached neighbor counts.
+    pub fn cached_next_cell_state(&self, x: usize, y: usize) -> bool {
+        self.next_state_with(x, y, self.cached_neighbor_count(x, y))
+    }
+
+    fn next_state_with(&self, x: usize, y: usize, neighbors_count: i32) -> bool {
+        let is_alive = self.grid.get(x, y);
+
+        // Locked cells keep whatever state the user gave them.
+        if self.locked.get(x, y) {
+            return is_alive;
+        }
+
+        self.rule.next_state(is_alive, neighbors_count)
+    }
+
+    fn apply_simulation_grid(&mut self) {
+        if self.show_activity {
+            let changed = self
+                .grid
+                .array
+                .iter()
+                .zip(&self.simulation_grid.array)
+                .enumerate()
+                .filter(|(_, (was_alive, is_alive))| was_alive != is_alive)
+                .map(|(index, _)| index)
+                .collect();
+            self.record_activity(changed);
+        }
+
+        if self.show_births {
+            self.born.resize(self.grid.array.len(), false);
+            for ((born, &was_alive), &is_alive) in self
+                .born
+                .iter_mut()
+                .zip(&self.grid.array)
+                .zip(&self.simulation_grid.array)
+            {
+                *born = is_alive && !was_alive;
+            }
+        }
+
+        if let Some(delta) = &mut self.delta {
+            let width = self.grid.width;
+            for (index, (&was_alive, &is_alive)) in self
+                .grid
+                .array
+                .iter()
+                .zip(&self.simulation_grid.array)
+                .enumerate()
+            {
+                if was_alive != is_alive {
+                    delta.push((index % width, index / width, is_alive));
+                }
+            }
+        }
+
+        for (&was_alive, &is_alive) in self.grid.array.iter().zip(&self.simulation_grid.array) {
+            match (was_alive, is_alive) {
+                (false, true) => self.total_births += 1,
+                (true, false) => self.total_deaths += 1,
+                _ => {}
+            }
+        }
+
+        self.grid.array = self.simulation_grid.array.clone();
+        self.invalidate_neighbor_counts();
+    }
+
+    /// Counts the gliders heading off the board's edge that `simulation_grid`
+    /// no longer has as a glider, because the dead cells past the edge cut
+    /// them off as they were leaving.
+    fn count_escaping_gliders(&mut self) {
+        let (width, height) = (self.grid.width as i32, self.grid.height as i32);
+        for glider in census::escaping_gliders(&self.grid) {
+            let left = glider.iter().map(|&(x, _)| x).min().unwrap_or(0) - 1;
+            let right = glider.iter().map(|&(x, _)| x).max().unwrap_or(0) + 1;
+            let top = glider.iter().map(|&(_, y)| y).min().unwrap_or(0) - 1;
+            let bottom = glider.iter().map(|&(_, y)| y).max().unwrap_or(0) + 1;
+
+            let next: Vec<(i32, i32)> = (top.max(0)..=bottom.min(height - 1))
+                .flat_map(|y| (left.max(0)..=right.min(width - 1)).map(move |x| (x, y)))
+                .filter(|&(x, y)| self.simulation_grid.get(x as usize, y as usize))
+                .collect();
+            if census::glider_heading(&next).is_none() {
+                self.escaped_gliders += 1;
+            }
+        }
+    }
+
+    /// Seeds `rng` afresh and remembers the seed for `reset_rng`.
+    pub fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = Rng::new(seed);
+    }
+
+    /// Rewinds `rng` to the start of its seed's sequence, so the same random
+    /// actions taken again play out the same way.
+    pub fn reset_rng(&mut self) {
+        self.rng = Rng::new(self.seed);
+        self.show_message(format!("Random numbers reset to seed {}", self.seed));
+    }
+
+    /// Replaces the board with random cells, each alive with chance `density`.
+    pub fn randomize(&mut self, density: f32) {
+        for cell in self.grid.array.iter_mut() {
+            *cell = self.rng.next_f32() < density;
+        }
+        self.invalidate_neighbor_counts();
+        self.start = None;
+    }
+
+    /// Replaces the board with the cells `formula` says are alive.
+    pub fn fill_from_formula(&mut self, formula: &Formula) {
+        let (width, height) = (self.grid.width, self.grid.height);
+        for (index, cell) in self.grid.array.iter_mut().enumerate() {
+            *cell = formula.is_alive(index % width, index / width, width, height);
+        }
+        self.invalidate_neighbor_counts();
+        self.start = None;
+    }
+
+    fn inject_noise(&mut self) {
+        let mut births = HashSet::new();
+        for (index, (cell, &locked)) in self
+            .grid
+            .array
+            .iter_mut()
+            .zip(&self.locked.array)
+            .enumerate()
+        {
+            let (x, y) = (index % self.grid.width, index / self.grid.width);
+            let frozen = self
+                .active_region
+                .is_some_and(|region| !region.contains(x, y));
+
+            if !*cell && !locked && !frozen && self.rng.next_f32() < self.noise_rate {
+                *cell = true;
+                self.total_births += 1;
+                if self.show_births {
+                    self.born[index] = true;
+                }
+                if self.delta.is_some() {
+                    births.insert((x, y));
+                }
+            }
+        }
+
+        if let Some(delta) = &mut self.delta {
+            // Noise only fills dead cells, so any of them already in the
+            // delta died this generation and hasn't changed after all.
+            delta.retain(|&(x, y, _)| !births.remove(&(x, y)));
+            delta.extend(births.into_iter().map(|(x, y)| (x, y, true)));
+        }
+    }
+
+    /// Fades the activity map by a generation, heats up the `changed` cells
+    /// and takes out the changes that just left the window.
+    fn record_activity(&mut self, changed: Vec<usize>) {
+        self.activity.resize(self.grid.array.len(), 0.0);
+        for heat in &mut self.activity {
+            *heat *= self.activity_decay;
+        }
+        for &index in &changed {
+            self.activity[index] += 1.0;
+        }
+
+        self.activity_changes.push_back(changed);
+        while self.activity_changes.len() > self.activity_window {
+            // By now each expired change has faded once per generation in
+            // the window.
+            let faded = self.activity_decay.powi(self.activity_window as i32);
+            for index in self.activity_changes.pop_front().unwrap_or_default() {
+                self.activity[index] = (self.activity[index] - faded).max(0.0);
+            }
+        }
+    }
+
+    fn reset_activity(&mut self) {
+        self.activity = vec![0.0; self.grid.array.len()];
+        self.activity_changes.clear();
+    }
+
+    fn update_trail(&mut self) {
+        // Keep the buffer in lockstep with the grid should its size ever change.
+        self.trail.resize(self.grid.array.len(), 0.0);
+
+        for (mark, &alive) in self.trail.iter_mut().zip(&self.grid.array) {
+            *mark = if alive {
+                1.0
+            } else {
+                (*mark - self.trail_decay).max(0.0)
+            };
+        }
+    }
+
+    pub fn process_event(&mut self, event: &Event, window: &RenderWindow) {
+        match event {
+            Event::MouseButtonPressed { button, x, y } => {
+                // The help panel covers the board, so don't edit cells behind it.
+                if self.show_help {
+                    return;
+                }
+                if self.show_rule_editor && *button == Button::LEFT {
+                    if let Some((survival, count)) = overlay::rule_editor_hit(window.size(), *x, *y)
+                    {
+                        self.toggle_rule_count(survival, count);
+                        return;
+                    }
+                }
+
+                let cell_pos = match self.get_cell_below_pixel(window, *x, *y) {
+                    Some(cell_pos) => cell_pos,
+                    None => return,
+                };
+
+                if *button == Button::RIGHT && bindings::shift_held() {
+                    self.selection = Some(Selection {
+                        start: cell_pos,
+                        end: cell_pos,
+                    });
+                    self.selecting = true;
+                } else if *button == Button::RIGHT {
+                    self.stroke = Some(Stroke {
+                        alive: false,
+                        last: cell_pos,
+                        moved: false,
+                    });
+                } else if let Some((pattern, x, y)) = self.placement() {
+                    self.stamp_pattern(&pattern, x, y);
+                    self.active_pattern = None;
+                } else if bindings::alt_held() {
+                    self.line = Some((cell_pos, cell_pos));
+                } else if bindings::ctrl_held() {
+                    self.toggle_lock(cell_pos);
+                } else if bindings::shift_held() {
+                    self.focused_cell = if self.focused_cell == Some(cell_pos) {
+                        None
+                    } else {
+                        Some(cell_pos)
+                    };
+                } else if *button == Button::LEFT {
+                    self.stroke = Some(Stroke {
+                        alive: true,
+                        last: cell_pos,
+                        moved: false,
+                    });
+                }
+            }
+            Event::MouseMoved { x, y } => {
+                let cell_pos = self.get_cell_below_pixel(window, *x, *y);
+                self.hovered_cell = cell_pos;
+
+                if let (true, Some(selection), Some(cell_pos)) =
+                    (self.selecting, &mut self.selection, cell_pos)
+                {
+                    selection.end = cell_pos;
+                }
+                if let (Some((_, end)), Some(cell_pos)) = (&mut self.line, cell_pos) {
+                    *end = cell_pos;
+                }
+                if let Some(cell_pos) = cell_pos {
+                    self.continue_stroke(cell_pos);
+                }
+            }
+            Event::MouseButtonReleased {
+                button: Button::LEFT,
+                ..
+            } => {
+                self.finish_stroke();
+                if let Some((start, end)) = self.line.take() {
+                    self.draw_line(
+                        start.x as isize,
+                        start.y as isize,
+                        end.x as isize,
+                        end.y as isize,
+                        true,
+                    );
+                }
+            }
+            Event::MouseButtonReleased {
+                button: Button::RIGHT,
+                ..
+            } => {
+                self.selecting = false;
+                self.finish_stroke();
+            }
+            Event::KeyPressed {
+                code, ctrl, shift, ..
+            } => match bindings::find_action(*code, *ctrl, *shift) {
+                Some(action @ (Action::InvertVisible | Action::ClearVisible)) => {
+                    self.edit_visible(action, window.view())
+                }
+                Some(action) => self.perform(action),
+                None => (),
+            },
+            _ => (),
+        }
+    }
+
+    pub fn perform(&mut self, action: Action) {
+        match action {
+            Action::TogglePause => self.paused = !self.paused,
+            Action::ToggleSandbox => self.sandbox = !self.sandbox,
+            Action::CycleBoundary => {
+                // Dead edges always fit, so this stops within one lap.
+                self.boundary = self.boundary.next();
+                while !self.topology.fits(self.boundary, self.grid.height) {
+                    self.boundary = self.boundary.next();
+                }
+                // Only the counts are stale; the board itself is unedited.
+                *self.neighbor_counts.get_mut() = None;
+                self.show_message(format!("Boundary: {}", self.boundary));
+            }
+            Action::ToggleUpdateMode => {
+                self.update_mode = self.update_mode.toggled();
+                self.show_message(format!("Update mode: {}", self.update_mode));
+            }
+            Action::ToggleYUp => {
+                self.y_up = !self.y_up;
+                let row_0 = if self.y_up { "bottom" } else { "top" };
+                self.show_message(format!("Row 0 at the {}", row_0));
+            }
+            Action::ShiftBoard(dx, dy) => {
+                self.grid.shift(dx, dy, self.boundary);
+                self.invalidate_neighbor_counts();
+            }
+            Action::Census => census::print_census(&self.grid),
+            Action::ReportMotion => ash::print_motion(self),
+            Action::ClearAsh => {
+                let cleared = self.clear_ash();
+                self.show_message(format!(
+                    "Cleared {} cells of still and oscillating ash, {} moving cells left",
+                    cleared,
+                    self.grid.population()
+                ));
+            }
+            Action::PasteRle => self.paste_rle(),
+            Action::SaveCrop => self.save_crop(),
+            Action::SaveCsv => self.save_csv(),
+            Action::TileContent => self.tile_content(),
+            Action::ToggleProbe => self.toggle_probe(),
+            Action::FlushProbes => self.flush_probes(),
+            Action::OverlayMirrored(axis) => self.overlay_mirrored(axis),
+            Action::Grow => self.grow(GROW_MARGIN),
+            Action::Shrink => self.shrink(),
+            Action::ToggleFollow => self.following = !self.following,
+            Action::ToggleTimings => self.show_timings = !self.show_timings,
+            Action::ToggleStatus => self.show_status = !self.show_status,
+            Action::TogglePopulationGraph => {
+                self.show_population_graph = !self.show_population_graph
+            }
+            Action::ToggleRulers => self.show_rulers = !self.show_rulers,
+            Action::ToggleBoundingBox => self.show_bounding_box = !self.show_bounding_box,
+            Action::ToggleQuietZone => self.toggle_quiet_zone(),
+            Action::CycleSymmetry => {
+                self.symmetry = self.symmetry.next();
+                self.show_message(format!("Symmetry brush: {}", self.symmetry));
+            }
+            Action::ToggleRuleEditor => self.show_rule_editor = !self.show_rule_editor,
+            Action::ToggleCellLabels => self.show_cell_labels = !self.show_cell_labels,
+            Action::ToggleMaxSpeed => self.max_speed = !self.max_speed,
+            Action::ResetRng => self.reset_rng(),
+            Action::RevertMutations => self.revert_mutations(),
+            Action::ApplyOfferedRule => match self.offered_rule {
+                Some(rule) => {
+                    self.set_rule(rule);
+                    self.show_message(format!("Switched to {}", rule));
+                }
+                None => self.show_message("No pasted pattern asked for another rule".to_string()),
+            },
+            Action::ToggleDifferences => {
+                self.show_differences = !self.show_differences;
+                if self.show_differences {
+                    self.report_difference();
+                }
+            }
+            Action::SetReference => {
+                let board = self.grid.clone();
+                self.set_reference(&board);
+                self.show_message("Current board is now the reference".to_string());
+            }
+            Action::SaveCheckpoint(slot) => self.save_checkpoint(slot),
+            Action::RestoreCheckpoint(slot) => self.restore_checkpoint(slot),
+            Action::Rewind => self.rewind(),
+            Action::AdjustPadding(steps) => {
+                // Always leave at least part of a cell to draw.
+                let largest = (self.cell_size.x.min(self.cell_size.y) - 1.0).max(0.0);
+                self.cell_padding =
+                    (self.cell_padding + steps as f32 * CELL_PADDING_STEP).clamp(0.0, largest);
+                self.show_message(format!("Cell padding: {}", self.cell_padding));
+            }
+            Action::AdjustNoise(steps) => {
+                self.noise_rate =
+                    (self.noise_rate + steps as f32 * NOISE_RATE_STEP).clamp(0.0, MAX_NOISE_RATE);
+                self.show_message(format!("Noise rate: {:.3}", self.noise_rate));
+            }
+            // These need the window's view, so `process_event` handles them.
+            Action::InvertVisible | Action::ClearVisible => (),
+            Action::ClearSelection => {
+                if let Some(Selection { start, end }) = self.selection {
+                    self.clear_region(start.x, start.y, end.x, end.y);
+                }
+            }
+            Action::ToggleActiveRegion => {
+                if self.active_region.take().is_some() {
+                    self.show_message("Simulating the whole board".to_string());
+                } else if let Some(selection) = self.selection.take() {
+                    self.active_region = Some(selection);
+                    self.selecting = false;
+                    let size = selection.size();
+                    self.show_message(format!(
+                        "Simulating only the {}x{} active region",
+                        size.x, size.y
+                    ));
+                } else {
+                    self.show_message("Select a region first".to_string());
+                }
+            }
+            Action::ToggleForbidden => self.toggle_forbidden(),
+            Action::RotatePattern => {
+                self.pattern_rotation = (self.pattern_rotation + 1) % 4;
+            }
+            Action::Deselect => {
+                self.active_pattern = None;
+                self.selection = None;
+                self.selecting = false;
+            }
+            Action::ToggleBirths => {
+                self.show_births = !self.show_births;
+                self.born.iter_mut().for_each(|born| *born = false);
+            }
+            Action::ToggleInspector => self.show_inspector = !self.show_inspector,
+            Action::ToggleInfluenced => self.show_influenced = !self.show_influenced,
+            Action::ToggleComponents => self.show_components = !self.show_components,
+            Action::ToggleTrail => {
+                self.show_trail = !self.show_trail;
+                self.trail.iter_mut().for_each(|mark| *mark = 0.0);
+            }
+            Action::ToggleActivity => {
+                self.show_activity = !self.show_activity;
+                self.reset_activity();
+            }
+            Action::ToggleHelp => {
+                if !self.text_overlays {
+                    bindings::print_help(self.seed);
+                } else if !self.show_help {
+                    self.show_help = true;
+                    self.help_page = 0;
+                } else if self.help_page + 1 < self.help_pages {
+                    self.help_page += 1;
+                } else {
+                    self.show_help = false;
+                }
+            }
+        }
+    }
+
+    /// The general path of `update`, which handles every boundary and locked
+    /// cells by asking each cell for its next state.
+    fn update_cell_by_cell(&mut self) {
+        let grid = &self.grid;
+        for y in 0..grid.height {
+            for x in 0..grid.width {
+                let is_going_to_live = self.next_cell_state(x, y);
+                self.simulation_grid.set(x, y, is_going_to_live);
+            }
+        }
+    }
+
+    /// Like `update_cell_by_cell`, but cells outside `region` are copied over
+    /// unchanged. In `AsyncScan` mode the region is scanned in place, as in
+    /// `update_async_scan`.
+    fn update_region(&mut self, region: Selection) {
+        self.simulation_grid.array.clone_from(&self.grid.array);
+        let in_place = self.update_mode == UpdateMode::AsyncScan;
+
+        let (top_left, bottom_right) = region.corners();
+        for y in top_left.y..=bottom_right.y {
+            for x in top_left.x..=bottom_right.x {
+                let is_going_to_live = self.next_cell_state(x, y);
+                if in_place {
+                    self.grid.set(x, y, is_going_to_live);
+                } else {
+                    self.simulation_grid.set(x, y, is_going_to_live);
+                }
+            }
+        }
+
+        if in_place {
+            std::mem::swap(&mut self.grid, &mut self.simulation_grid);
+        }
+    }
+
+    /// The `rule_table` path of `update`. Cells next to any non-zero state
+    /// count it as a live neighbor, as in the table's totalistic counts.
+    /// Like `update_region`, cells outside `active_region` stay as they are.
+    fn update_multi_state(&mut self) {
+        self.sync_cell_states();
+        let Some(table) = &self.rule_table else {
+            return;
+        };
+
+        let next: Vec<u8> = self
+            .grid
+            .cells()
+            .map(|(x, y, _)| {
+                let state = self.cell_states[self.grid.get_index(x, y)];
+                let frozen = self
+                    .active_region
+                    .is_some_and(|region| !region.contains(x, y));
+                if frozen || self.locked.get(x, y) {
+                    state
+                } else {
+                    table.next_state(state, self.get_neighbors_count(x, y) as usize)
+                }
+            })
+            .collect();
+
+        for (index, &state) in next.iter().enumerate() {
+            self.simulation_grid.array[index] = state != 0;
+        }
+        self.cell_states = next;
+    }
+
+    /// Brings `cell_states` in line with edits made straight to `grid`:
+    /// cells cleared there go to state 0 and cells set there to state 1.
+    /// A resized board starts over from `grid`.
+    fn sync_cell_states(&mut self) {
+        if self.cell_states.len() != self.grid.array.len() {
+            self.cell_states = vec![0; self.grid.array.len()];
+        }
+        for (state, &alive) in self.cell_states.iter_mut().zip(&self.grid.array) {
+            if !alive {
+                *state = 0;
+            } else if *state == 0 {
+                *state = 1;
+            }
+        }
+    }
+
+    /// Runs `table` in place of `rule` from the next generation on, or goes
+    /// back to `rule` with `None`. Live cells start at state 1.
+    pub fn set_rule_table(&mut self, table: Option<RuleTable>) {
+        self.rule_table = table;
+        self.cell_states = vec![0; self.grid.array.len()];
+        self.sync_cell_states();
+    }
+
+    pub fn rule_table(&self) -> Option<&RuleTable> {
+        self.rule_table.as_ref()
+    }
+
+    /// The state of the cell at `(x, y)`: its `rule_table` state when there
+    /// is a table, otherwise 1 for live and 0 for dead.
+    pub fn cell_state(&self, x: usize, y: usize) -> u8 {
+        let index = self.grid.get_index(x, y);
+        match self.cell_states.get(index) {
+            Some(&state) if self.rule_table.is_some() && self.grid.get(x, y) => state.max(1),
+            _ => u8::from(self.grid.get(x, y)),
+        }
+    }
+
+    /// The `AsyncScan` path of `update`. Cells are written straight into
+    /// `grid` as they're worked out, so later cells read their earlier
+    /// neighbors' new states. `simulation_grid` keeps the old board, and the
+    /// two are swapped at the end so `update` can finish as usual.
+    fn update_async_scan(&mut self) {
+        self.simulation_grid.array.clone_from(&self.grid.array);
+
+        for y in 0..self.grid.height {
+            for x in 0..self.grid.width {
+                let is_going_to_live = self.next_cell_state(x, y);
+                self.grid.set(x, y, is_going_to_live);
+            }
+        }
+
+        std::mem::swap(&mut self.grid, &mut self.simulation_grid);
+    }
+
+    /// Advances the board by one generation.
+    ///
+    /// Every cell's next state is computed from the current `grid` alone and
+    /// written to `simulation_grid`, which only replaces `grid` once the whole
+    /// board is done. Never write into `grid` inside the loop: cells later in
+    /// scan order would then see their neighbors' new states instead of the
+    /// old ones, and a blinker would no longer oscillate. `AsyncScan` mode
+    /// does exactly that, on purpose.
+    pub fn update(&mut self) {
+        if self.paused || self.sandbox {
+            return;
+        }
+
+        if self.start.is_none() {
+            self.start = Some(Checkpoint {
+                grid: self.grid.clone(),
+                generation: self.generation,
+            });
+        }
+
+        let step_clock = Clock::start();
+        if self.rule_table.is_some() {
+            self.update_multi_state();
+        } else if let Some(region) = self.active_region {
+            self.update_region(region);
+        } else if self.update_mode == UpdateMode::AsyncScan {
+            self.update_async_scan();
+        } else if self.rule == Rule::CONWAY
+            && self.topology == GridTopology::Square
+            && self.boundary == Boundary::Dead
+            && !self.locked.array.contains(&true)
+        {
+            PackedGrid::from_grid(&self.grid)
+                .step()
+                .write_to(&mut self.simulation_grid);
+        } else {
+            self.update_cell_by_cell();
+        }
+        if self.count_escaped_gliders
+            && self.boundary == Boundary::Dead
+            && self.topology == GridTopology::Square
+        {
+            self.count_escaping_gliders();
+        }
+        self.step_time = step_clock.elapsed_time();
+
+        // Only compare boards when someone is listening.
+        let stable =
+            !self.callbacks.stable.is_empty() && self.simulation_grid.array == self.grid.array;
+
+        self.apply_simulation_grid();
+        self.generation += 1;
+
+        if self.log_violations {
+            let violations = self.violations();
+            if violations > 0 {
+                println!(
+                    "Generation {}: {} live cells in forbidden cells",
+                    self.generation, violations
+                );
+            }
+        }
+
+        if self.noise_rate > 0.0 {
+            self.inject_noise();
+        }
+
+        if let Some(interval) = self.mutation_interval {
+            if self.generation.is_multiple_of(interval) {
+                self.mutate_rule();
+            }
+        }
+
+        if self.show_trail {
+            self.update_trail();
+        }
+
+        for &(x, y) in &self.probes {
+            self.probe_log.push(ProbeReading {
+                generation: self.generation,
+                x,
+                y,
+                alive: self.grid.get(x, y),
+            });
+        }
+
+        if self.population_history.len() == POPULATION_HISTORY_LENGTH {
+            self.population_history.pop_front();
+        }
+        self.population_history.push_back(self.grid.population());
+
+        if let Some(remaining) = self.remaining_ticks {
+            if remaining <= 1 {
+                self.remaining_ticks = None;
+                self.paused = true;
+                println!("Paused at generation {}", self.generation);
+            } else {
+                self.remaining_ticks = Some(remaining - 1);
+            }
+        }
+
+        self.run_callbacks(|callbacks| &mut callbacks.generation);
+        if stable {
+            self.run_callbacks(|callbacks| &mut callbacks.stable);
+        }
+    }
+
+    /// Whether `max_generation` generations have passed since the board was
+    /// last cleared.
+    pub fn at_generation_cap(&self) -> bool {
+        self.max_generation
+            .is_some_and(|cap| self.generation.saturating_sub(self.cleared_at) >= cap)
+    }
+
+    /// Like `update`, but also returns every cell the generation changed, as
+    /// `(x, y, now_alive)` in no particular order. Nothing is returned while
+    /// paused.
+    pub fn update_with_delta(&mut self) -> Vec<(usize, usize, bool)> {
+        self.delta = Some(Vec::new());
+        self.update();
+        self.delta.take().unwrap_or_default()
+    }
+
+    pub fn draw(&self, target: &mut impl RenderTarget) {
+        let grid = &self.grid;
+
+        let mut cell_shape = RectangleShape::new();
+
+        // The negative origin moves every cell in by half the padding, so
+        // callers can keep positioning it at `cell_position`.
+        let padding = self.cell_padding;
+        cell_shape.set_size(Vector2f::new(
+            (self.cell_size.x - padding).max(0.0),
+            (self.cell_size.y - padding).max(0.0),
+        ));
+        cell_shape.set_origin(Vector2f::new(-padding / 2.0, -padding / 2.0));
+
+        // One rectangle under the whole board stands in for every dead cell;
+        // live cells are drawn over it below.
+        if let Some(dead_cell_color) = self.dead_cell_color {
+            let mut board = RectangleShape::with_size(self.world_size());
+            board.set_fill_color(dead_cell_color);
+            target.draw(&board);
+        }
+
+        if self.show_activity {
+            let hottest = self.activity.iter().copied().fold(0.0, f32::max);
+            for y in 0..grid.height {
+                for x in 0..grid.width {
+                    let heat = self.activity[grid.get_index(x, y)];
+                    if heat > 0.0 {
+                        let level = heat / hottest;
+                        let mut color = heat_color(level);
+                        *color.alpha_mut() = (level.sqrt() * ACTIVITY_MAX_ALPHA) as u8;
+
+                        cell_shape.set_fill_color(color);
+                        cell_shape.set_position(self.cell_position(x, y));
+                        target.draw(&cell_shape);
+                    }
+                }
+            }
+        }
+
+        if self.show_trail {
+            for y in 0..grid.height {
+                for x in 0..grid.width {
+                    let mark = self.trail[grid.get_index(x, y)];
+                    if mark > 0.0 && !grid.get(x, y) {
+                        let mut color = self.cell_color;
+                        *color.alpha_mut() = (mark * TRAIL_MAX_ALPHA) as u8;
+
+                        cell_shape.set_fill_color(color);
+                        cell_shape.set_position(self.cell_position(x, y));
+                        target.draw(&cell_shape);
+                    }
+                }
+            }
+        }
+
+        let component_ids = if self.show_components {
+            grid.component_ids()
+        } else {
+            Vec::new()
+        };
+
+        for (x, y) in grid.live_cells() {
+            let index = grid.get_index(x, y);
+            let color = if self.show_births && self.born[index] {
+                self.born_color
+            } else if let Some(Some(id)) = component_ids.get(index) {
+                component_color(*id)
+            } else {
+                self.palette_color(x, y).unwrap_or(self.cell_color)
+            };
+            let color = match &self.rule_table {
+                Some(table) => fade_by_state(color, self.cell_state(x, y), table.states()),
+                None => color,
+            };
+
+            cell_shape.set_fill_color(color);
+            cell_shape.set_position(self.cell_position(x, y));
+            target.draw(&cell_shape);
+        }
+
+        self.draw_locked(target, &mut cell_shape);
+        self.draw_forbidden(target, &mut cell_shape);
+
+        self.draw_differences(target, &mut cell_shape);
+        self.draw_line_preview(target, &mut cell_shape);
+        self.draw_placement(target, &mut cell_shape);
+        self.draw_influence_region(target, &mut cell_shape);
+        self.draw_active_region(target);
+        self.draw_bounding_box(target);
+        self.draw_quiet_zone(target);
+        self.draw_selection(target);
+    }
+
+    fn palette_color(&self, x: usize, y: usize) -> Option<Color> {
+        let palette = self.palette.as_ref()?;
+        let (x, y) = (u32::try_from(x).ok()?, u32::try_from(y).ok()?);
+        let size = palette.size();
+        (x < size.x && y < size.y).then(|| palette.pixel_at(x, y))
+    }
+
+    fn draw_differences(&self, target: &mut impl RenderTarget, cell_shape: &mut RectangleShape) {
+        let reference = match (self.show_differences, self.sized_reference()) {
+            (true, Some(reference)) => reference,
+            _ => return,
+        };
+
+        cell_shape.set_fill_color(Color::rgba(255, 60, 60, 140));
+        for (x, y, alive) in self.grid.cells() {
+            if alive != reference.get(x, y) {
+                cell_shape.set_position(self.cell_position(x, y));
+                target.draw(cell_shape);
+            }
+        }
+    }
+
+    fn draw_placement(&self, target: &mut impl RenderTarget, cell_shape: &mut RectangleShape) {
+        let Some((pattern, x, y)) = self.placement() else {
+            return;
+        };
+
+        let mut color = self.cell_color;
+        *color.alpha_mut() = 128;
+        cell_shape.set_fill_color(color);
+
+        for (px, py) in pattern.live_cells() {
+            let (bx, by) = (x + px as isize, y + py as isize);
+            if (0..self.grid.width as isize).contains(&bx)
+                && (0..self.grid.height as isize).contains(&by)
+            {
+                cell_shape.set_position(self.cell_position(bx as usize, by as usize));
+                target.draw(cell_shape);
+            }
+        }
+    }
+
+    fn draw_line_preview(&self, target: &mut impl RenderTarget, cell_shape: &mut RectangleShape) {
+        let Some((start, end)) = self.line else {
+            return;
+        };
+
+        let mut color = self.cell_color;
+        *color.alpha_mut() = 128;
+        cell_shape.set_fill_color(color);
+
+        let points = line_points(
+            start.x as isize,
+            start.y as isize,
+            end.x as isize,
+            end.y as isize,
+        );
+        for (x, y) in points {
+            cell_shape.set_position(self.cell_position(x as usize, y as usize));
+            target.draw(cell_shape);
+        }
+    }
+
+    fn draw_influence_region(
+        &self,
+        target: &mut impl RenderTarget,
+        cell_shape: &mut RectangleShape,
+    ) {
+        let Some(focus) = self.focused_cell else {
+            return;
+        };
+
+        cell_shape.set_fill_color(Color::rgba(255, 230, 60, 110));
+        for (x, y) in self.influence_region() {
+            cell_shape.set_position(self.cell_position(x, y));
+            target.draw(cell_shape);
+        }
+
+        let mut outline = RectangleShape::with_size(self.cell_size);
+        outline.set_position(self.cell_position(focus.x, focus.y));
+        outline.set_fill_color(Color::TRANSPARENT);
+        outline.set_outline_color(Color::rgb(255, 230, 60));
+        outline.set_outline_thickness(-2.0);
+        target.draw(&outline);
+    }
+
+    fn draw_active_region(&self, target: &mut impl RenderTarget) {
+        let Some(region) = self.active_region else {
+            return;
+        };
+        let bounds = self.region_world_bounds(region);
+
+        let mut outline = RectangleShape::new();
+        outline.set_position((bounds.left, bounds.top));
+        outline.set_size((bounds.width, bounds.height));
+        outline.set_fill_color(Color::TRANSPARENT);
+        outline.set_outline_color(Color::rgb(255, 80, 200));
+        outline.set_outline_thickness(-2.0);
+
+        target.draw(&outline);
+    }
+
+    fn draw_quiet_zone(&self, target: &mut impl RenderTarget) {
+        let Some(zone) = self.quiet_zone else {
+            return;
+        };
+        let bounds = self.region_world_bounds(zone);
+
+        let mut outline = RectangleShape::new();
+        outline.set_position((bounds.left, bounds.top));
+        outline.set_size((bounds.width, bounds.height));
+        outline.set_fill_color(Color::rgba(80, 255, 160, 30));
+        outline.set_outline_color(Color::rgb(80, 255, 160));
+        outline.set_outline_thickness(-2.0);
+
+        target.draw(&outline);
+    }
+
+    fn draw_bounding_box(&self, target: &mut impl RenderTarget) {
+        if !self.show_bounding_box {
+            return;
+        }
+        let Some(((left, top), (right, bottom))) = self.grid.content_bounds() else {
+            return;
+        };
+        let bounds = self.region_world_bounds(Selection {
+            start: Vector2::new(left, top),
+            end: Vector2::new(right, bottom),
+        });
+
+        let mut outline = RectangleShape::new();
+        outline.set_position((bounds.left, bounds.top));
+        outline.set_size((bounds.width, bounds.height));
+        outline.set_fill_color(Color::TRANSPARENT);
+        outline.set_outline_color(Color::rgb(60, 220, 220));
+        outline.set_outline_thickness(-1.0);
+
+        target.draw(&outline);
+    }
+
+    /// Live locked cells are drawn solid in `locked_color`, dead ones as a
+    /// faint tint of it so walls of dead cells are visible too.
+    fn draw_locked(&self, target: &mut impl RenderTarget, cell_shape: &mut RectangleShape) {
+        let mut dead_color = self.locked_color;
+        *dead_color.alpha_mut() = 64;
+
+        for (x, y) in self.locked.live_cells() {
+            cell_shape.set_fill_color(if self.grid.get(x, y) {
+                self.locked_color
+            } else {
+                dead_color
+            });
+            cell_shape.set_position(self.cell_position(x, y));
+            target.draw(cell_shape);
+        }
+    }
+
+    /// Live cells in forbidden cells are drawn solid in `forbidden_color`,
+    /// the rest of the forbidden area as a faint tint of it.
+    fn draw_forbidden(&self, target: &mut impl RenderTarget, cell_shape: &mut RectangleShape) {
+        let mut dead_color = self.forbidden_color;
+        *dead_color.alpha_mut() = 48;
+
+        for (x, y) in self.forbidden.live_cells() {
+            cell_shape.set_fill_color(if self.grid.get(x, y) {
+                self.forbidden_color
+            } else {
+                dead_color
+            });
+            cell_shape.set_position(self.cell_position(x, y));
+            target.draw(cell_shape);
+        }
+    }
+
+    fn draw_selection(&self, target: &mut impl RenderTarget) {
+        let bounds = match self.selection_world_bounds() {
+            Some(bounds) => bounds,
+            None => return,
+        };
+
+        let mut outline = RectangleShape::new();
+        outline.set_position((bounds.left, bounds.top));
+        outline.set_size((bounds.width, bounds.height));
+        outline.set_fill_color(Color::rgba(80, 160, 255, 40));
+        outline.set_outline_color(Color::rgb(80, 160, 255));
+        outline.set_outline_thickness(-1.0);
+
+        target.draw(&outline);
+    }
+
+    /// The area covered by the selection, in world coordinates.
+    pub fn selection_world_bounds(&self) -> Option<FloatRect> {
+        Some(self.region_world_bounds(self.selection?))
+    }
+
+    /// The area covered by a rectangle of cells, in world coordinates.
+    pub fn region_world_bounds(&self, region: Selection) -> FloatRect {
+        let (top_left, bottom_right) = region.corners();
+        let size = region.size();
+
+        // With `y_up` the region's last row is the one drawn highest.
+        let first = self.cell_position(top_left.x, top_left.y);
+        let last = self.cell_position(bottom_right.x, bottom_right.y);
+
+        FloatRect::new(
+            first.x,
+            first.y.min(last.y),
+            size.x as f32 * self.cell_size.x,
+            size.y as f32 * self.cell_size.y,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A game with a horizontal blinker through the middle of a 5x5 board.
+    fn blinker() -> Game {
+        let mut game = Game::new(5, 5);
+        for x in 1..=3 {
+            game.grid.set(x, 2, true);
+        }
+        game
+    }
+
+    fn live_cells(game: &Game) -> Vec<(usize, usize)> {
+        game.grid.live_cells().collect()
+    }
+
+    /// Two live cells side by side on a 5x5 board, under a three-state
+    /// table where cells are born next to exactly two others and then fade
+    /// through state 2 back to 0.
+    fn fading_pair() -> Game {
+        let table: RuleTable = "states = 3
+0 2       -> 1
+0 0,1,3-8 -> 0
+1 0-8     -> 2
+2 0-8     -> 0
+"
+        .parse()
+        .unwrap();
+        let mut game = Game::new(5, 5);
+        game.grid.set(2, 2, true);
+        game.grid.set(3, 2, true);
+        game.set_rule_table(Some(table));
+        game
+    }
+
+    fn check_blinker_oscillates(mut game: Game) {
+        game.update();
+        assert_eq!(live_cells(&game), [(2, 1), (2, 2), (2, 3)]);
+        game.update();
+        assert_eq!(live_cells(&game), [(1, 2), (2, 2), (3, 2)]);
+    }
+
+    #[test]
+    fn blinker_oscillates_on_the_packed_path() {
+        check_blinker_oscillates(blinker());
+    }
+
+    #[test]
+    fn blinker_oscillates_cell_by_cell() {
+        // A locked cell keeps `update` off the packed path. The corner is
+        // dead and stays dead either way.
+        let mut game = blinker();
+        game.locked.set(0, 0, true);
+        check_blinker_oscillates(game);
+    }
+
+    #[test]
+    fn line_points_are_contiguous_in_every_octant() {
+        let ends = [
+            (7, 3),
+            (3, 7),
+            (-3, 7),
+            (-7, 3),
+            (-7, -3),
+            (-3, -7),
+            (3, -7),
+            (7, -3),
+            (5, 0),
+            (0, -5),
+            (4, 4),
+            (-4, 4),
+            (0, 0),
+        ];
+        for (x1, y1) in ends {
+            let points = line_points(2, -1, 2 + x1, -1 + y1);
+            assert_eq!(points.first(), Some(&(2, -1)));
+            assert_eq!(points.last(), Some(&(2 + x1, -1 + y1)));
+            assert_eq!(points.len() as isize, x1.abs().max(y1.abs()) + 1);
+            for pair in points.windows(2) {
+                let (dx, dy) = (pair[1].0 - pair[0].0, pair[1].1 - pair[0].1);
+                assert!(
+                    dx.abs() <= 1 && dy.abs() <= 1 && (dx, dy) != (0, 0),
+                    "line to {},{} jumps from {:?} to {:?}",
+                    x1,
+                    y1,
+                    pair[0],
+                    pair[1]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn async_scan_breaks_the_blinker() {
+        let mut game = blinker();
+        game.update_mode = UpdateMode::AsyncScan;
+        game.update();
+
+        // (2, 1) is born first and (3, 1) is born next to it. By the time
+        // the scan reaches the blinker, those new cells keep both ends alive
+        // and crowd out the middle.
+        assert_eq!(live_cells(&game), [(2, 1), (3, 1), (1, 2), (3, 2)]);
+        assert_ne!(
+            live_cells(&game),
+            live_cells(&{
+                let mut synchronous = blinker();
+                synchronous.update();
+                synchronous
+            })
+        );
+    }
+
+    #[test]
+    fn async_scan_applies_inside_the_active_region() {
+        let mut game = blinker();
+        game.update_mode = UpdateMode::AsyncScan;
+        game.active_region = Some(Selection {
+            start: Vector2::new(0, 0),
+            end: Vector2::new(4, 4),
+        });
+        game.update();
+
+        assert_eq!(live_cells(&game), [(2, 1), (3, 1), (1, 2), (3, 2)]);
+    }
+
+    #[test]
+    fn cell_below_position_on_a_wide_board_of_tall_cells() {
+        let game = Game::try_new(GameConfig {
+            width: 8,
+            height: 3,
+            cell_size: Vector2f::new(10.0, 25.0),
+            ..GameConfig::default()
+        })
+        .unwrap();
+        let cell_at = |x, y| game.get_cell_below_position(Vector2f::new(x, y));
+
+        assert_eq!(cell_at(0.0, 0.0), Some(Vector2::new(0, 0)));
+        assert_eq!(cell_at(75.0, 10.0), Some(Vector2::new(7, 0)));
+        assert_eq!(cell_at(15.0, 60.0), Some(Vector2::new(1, 2)));
+        assert_eq!(cell_at(79.9, 74.9), Some(Vector2::new(7, 2)));
+
+        assert_eq!(cell_at(80.0, 10.0), None);
+        assert_eq!(cell_at(5.0, 75.0), None);
+        assert_eq!(cell_at(-0.1, 10.0), None);
+        assert_eq!(cell_at(5.0, -0.1), None);
+    }
+
+    #[test]
+    fn packed_step_matches_cell_by_cell_on_random_boards() {
+        let mut rng = Rng::new(125);
+        // Widths on either side of the 64 cells a packed word holds, then
+        // some picked at random.
+        let widths = [1, 63, 64, 65, 127, 128, 129]
+            .into_iter()
+            .chain((0..100).map(|_| 1 + (rng.next_u64() % 200) as usize))
+            .collect::<Vec<_>>();
+
+        for width in widths {
+            let height = 1 + (rng.next_u64() % 30) as usize;
+            let density = rng.next_f32();
+            let mut game = Game::new(width, height);
+            for y in 0..height {
+                for x in 0..width {
+                    game.grid.set(x, y, rng.next_f32() < density);
+                }
+            }
+
+            let mut packed = BoolGrid2D::new(width, height);
+            PackedGrid::from_grid(&game.grid)
+                .step()
+                .write_to(&mut packed);
+            game.update_cell_by_cell();
+            assert_eq!(
+                packed, game.simulation_grid,
+                "{}x{} board at density {}",
+                width, height, density
+            );
+        }
+    }
+
+    #[test]
+    fn rule_tables_with_more_states_run_on_the_board() {
+        let mut game = fading_pair();
+        assert_eq!(game.cell_state(2, 2), 1);
+
+        game.update();
+        assert_eq!(game.cell_state(2, 2), 2);
+        assert_eq!(game.cell_state(3, 2), 2);
+        assert_eq!(
+            live_cells(&game),
+            [(2, 1), (3, 1), (2, 2), (3, 2), (2, 3), (3, 3)]
+        );
+        assert_eq!(game.cell_state(2, 1), 1);
+
+        game.update();
+        assert_eq!(game.cell_state(2, 2), 0);
+        assert_eq!(game.cell_state(2, 1), 2);
+    }
+
+    #[test]
+    fn rule_tables_leave_cells_outside_the_active_region_alone() {
+        let mut game = fading_pair();
+        game.active_region = Some(Selection {
+            start: Vector2::new(0, 0),
+            end: Vector2::new(2, 4),
+        });
+
+        game.update();
+        assert_eq!(game.cell_state(2, 2), 2);
+        assert_eq!(game.cell_state(3, 2), 1);
+        assert_eq!(live_cells(&game), [(2, 1), (2, 2), (3, 2), (2, 3)]);
+    }
+
+    #[test]
+    fn odd_hexagonal_boards_keep_dead_edges() {
+        let config = GameConfig {
+            width: 10,
+            height: 9,
+            topology: GridTopology::Hexagonal,
+            boundary: Boundary::Toroidal,
+            ..GameConfig::default()
+        };
+        assert_eq!(
+            Game::try_new(config).err(),
+            Some(GameError::OddHexagonalHeight {
+                height: 9,
+                boundary: Boundary::Toroidal
+            })
+        );
+
+        let mut game = Game::try_new(GameConfig {
+            height: 10,
+            ..config
+        })
+        .unwrap();
+        game.resize(10, 9, Anchor::TopLeft);
+        assert_eq!(game.boundary, Boundary::Dead);
+    }
+
+    #[test]
+    fn noise_reviving_a_cell_takes_it_out_of_the_delta() {
+        let mut game = Game::new(3, 3);
+        game.grid.set(1, 1, true);
+        game.noise_rate = 1.0;
+
+        // The lone cell dies and noise brings every dead cell to life.
+        let mut delta = game.update_with_delta();
+        delta.sort();
+        let mut births: Vec<_> = game
+            .grid
+            .live_cells()
+            .filter(|&cell| cell != (1, 1))
+            .map(|(x, y)| (x, y, true))
+            .collect();
+        births.sort();
+        assert_eq!(births.len(), 8);
+        assert_eq!(delta, births);
+    }
+
+    #[test]
+    fn influenced_cells_match_the_scan_they_replace() {
+        for (topology, boundary) in [
+            (GridTopology::Square, Boundary::Dead),
+            (GridTopology::Square, Boundary::KleinBottle),
+            (GridTopology::Hexagonal, Boundary::Toroidal),
+            (GridTopology::Hexagonal, Boundary::ProjectivePlane),
+        ] {
+            let mut game = Game::try_new(GameConfig {
+                width: 6,
+                height: 6,
+                topology,
+                boundary,
+                ..GameConfig::default()
+            })
+            .unwrap();
+            game.show_influenced = true;
+            game.focused_cell = Some(Vector2::new(0, 5));
+
+            let mut region = game.influence_region();
+            region.sort();
+            region.dedup();
+            let mut scanned: Vec<_> = game
+                .grid
+                .cells()
+                .map(|(x, y, _)| (x, y))
+                .filter(|&(x, y)| game.neighbors(x, y).any(|cell| cell == (0, 5)))
+                .collect();
+            scanned.sort();
+            assert_eq!(region, scanned, "{} cells on a {}", topology, boundary);
+        }
+    }
+
+    #[test]
+    fn locked_cells_keep_their_state_through_update() {
+        let mut game = blinker();
+        // The ends of the blinker would die and the cells above and below
+        // its middle would be born.
+        game.locked.set(1, 2, true);
+        game.locked.set(2, 1, true);
+
+        game.update();
+        assert_eq!(live_cells(&game), [(1, 2), (2, 2), (2, 3)]);
+        game.update();
+        assert!(game.grid.get(1, 2));
+        assert!(!game.grid.get(2, 1));
+    }
+}
@@ -0,0 +1,859 @@
+use std::fmt;
+
+/// What lies beyond the edges of the grid.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Boundary {
+    /// Everything outside the grid is permanently dead.
+    Dead,
+    /// Opposite edges are joined, so the grid is a torus.
+    Toroidal,
+    /// Left and right edges are joined as on a torus, but crossing the top or
+    /// bottom edge mirrors the column.
+    KleinBottle,
+    /// Both pairs of edges are joined with a twist: crossing the top or bottom
+    /// mirrors the column, crossing the left or right mirrors the row.
+    ProjectivePlane,
+}
+
+/// How cells are laid out, which decides their neighbors.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum GridTopology {
+    /// Square cells with the 8 neighbors of the Moore neighborhood.
+    #[default]
+    Square,
+    /// Hexagonal cells with 6 neighbors each, stored in offset rows: every
+    /// odd row sits half a cell to the right of the rows around it.
+    Hexagonal,
+}
+
+/// A direction to mirror the board in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Axis {
+    /// Swaps left and right.
+    Horizontal,
+    /// Swaps top and bottom.
+    Vertical,
+}
+
+/// Which part of the board `BoolGrid2D::resize` keeps in place.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Anchor {
+    /// The top-left cell stays put, so cropping cuts off the right and
+    /// bottom.
+    #[default]
+    TopLeft,
+    /// The middle stays in the middle, cropping or adding evenly on all
+    /// sides.
+    Center,
+}
+
+/// How `BoolGrid2D::combine` merges a cell with the other board's.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BoolOp {
+    /// Alive where both boards are.
+    And,
+    /// Alive where either board is.
+    Or,
+    /// Alive where exactly one board is.
+    Xor,
+    /// Alive where this board is and the other isn't.
+    AndNot,
+}
+
+impl BoolOp {
+    pub fn apply(self, a: bool, b: bool) -> bool {
+        match self {
+            BoolOp::And => a && b,
+            BoolOp::Or => a || b,
+            BoolOp::Xor => a != b,
+            BoolOp::AndNot => a && !b,
+        }
+    }
+}
+
+/// A character in a plaintext pattern that's neither a cell nor part of a
+/// comment. Lines start at 1.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct InvalidPlaintext {
+    pub line: usize,
+    pub character: char,
+}
+
+impl fmt::Display for InvalidPlaintext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}: expected `.` or `O`, got {:?}",
+            self.line, self.character
+        )
+    }
+}
+
+impl std::error::Error for InvalidPlaintext {}
+
+/// A CSV row that isn't a pair of cell coordinates. Lines start at 1.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct InvalidCsv {
+    pub line: usize,
+    pub text: String,
+}
+
+impl fmt::Display for InvalidCsv {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}: expected X,Y with whole numbers, got {:?}",
+            self.line, self.text
+        )
+    }
+}
+
+impl std::error::Error for InvalidCsv {}
+
+impl Boundary {
+    /// Moves `(x, y)` by `(dx, dy)` on a `width` by `height` grid, returning
+    /// `None` if the destination falls off a dead edge.
+    pub fn offset(
+        self,
+        x: usize,
+        y: usize,
+        dx: isize,
+        dy: isize,
+        width: usize,
+        height: usize,
+    ) -> Option<(usize, usize)> {
+        let (width, height) = (width as isize, height as isize);
+        let nx = x as isize + dx;
+        let ny = y as isize + dy;
+
+        if self == Boundary::Dead {
+            return if (0..width).contains(&nx) && (0..height).contains(&ny) {
+                Some((nx as usize, ny as usize))
+            } else {
+                None
+            };
+        }
+
+        let mut wrapped_x = nx.rem_euclid(width);
+        let mut wrapped_y = ny.rem_euclid(height);
+
+        // Every odd number of trips across a twisted seam leaves the cell
+        // mirrored along the other axis.
+        let crossed_vertically = ny.div_euclid(height) % 2 != 0;
+        let crossed_horizontally = nx.div_euclid(width) % 2 != 0;
+
+        let (flip_x, flip_y) = match self {
+            Boundary::Dead | Boundary::Toroidal => (false, false),
+            Boundary::KleinBottle => (crossed_vertically, false),
+            Boundary::ProjectivePlane => (crossed_vertically, crossed_horizontally),
+        };
+
+        if flip_x {
+            wrapped_x = width - 1 - wrapped_x;
+        }
+        if flip_y {
+            wrapped_y = height - 1 - wrapped_y;
+        }
+
+        Some((wrapped_x as usize, wrapped_y as usize))
+    }
+
+    pub fn next(self) -> Boundary {
+        match self {
+            Boundary::Dead => Boundary::Toroidal,
+            Boundary::Toroidal => Boundary::KleinBottle,
+            Boundary::KleinBottle => Boundary::ProjectivePlane,
+            Boundary::ProjectivePlane => Boundary::Dead,
+        }
+    }
+}
+
+const SQUARE_NEIGHBORS: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+/// The diagonal neighbors of a hexagonal cell lean towards the side its
+/// row is shifted away from, so even and odd rows need their own offsets.
+const HEX_NEIGHBORS_EVEN_ROW: [(isize, isize); 6] =
+    [(-1, -1), (0, -1), (-1, 0), (1, 0), (-1, 1), (0, 1)];
+const HEX_NEIGHBORS_ODD_ROW: [(isize, isize); 6] =
+    [(0, -1), (1, -1), (-1, 0), (1, 0), (0, 1), (1, 1)];
+
+impl GridTopology {
+    /// The `(dx, dy)` steps to the neighbors of a cell in row `y`.
+    pub fn neighbor_offsets(self, y: usize) -> &'static [(isize, isize)] {
+        match self {
+            GridTopology::Square => &SQUARE_NEIGHBORS,
+            GridTopology::Hexagonal if y.is_multiple_of(2) => &HEX_NEIGHBORS_EVEN_ROW,
+            GridTopology::Hexagonal => &HEX_NEIGHBORS_ODD_ROW,
+        }
+    }
+
+    /// Whether a `height`-row board of this topology can take `boundary`.
+    /// Odd hexagonal rows are shifted, so a seam joining the last row to
+    /// row 0 only lines up when the board has an even number of rows.
+    pub fn fits(self, boundary: Boundary, height: usize) -> bool {
+        self == GridTopology::Square || boundary == Boundary::Dead || height.is_multiple_of(2)
+    }
+
+    /// How far row `y` is drawn to the right of row 0, in cells.
+    pub fn row_shift(self, y: usize) -> f32 {
+        match self {
+            GridTopology::Hexagonal if !y.is_multiple_of(2) => 0.5,
+            _ => 0.0,
+        }
+    }
+}
+
+impl fmt::Display for GridTopology {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            GridTopology::Square => "square",
+            GridTopology::Hexagonal => "hexagonal",
+        })
+    }
+}
+
+impl fmt::Display for Boundary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Boundary::Dead => "dead edges",
+            Boundary::Toroidal => "torus",
+            Boundary::KleinBottle => "Klein bottle",
+            Boundary::ProjectivePlane => "projective plane",
+        })
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BoolGrid2D {
+    pub(crate) array: Vec<bool>,
+    pub(crate) width: usize,
+    pub(crate) height: usize,
+}
+
+impl BoolGrid2D {
+    pub fn new(width: usize, height: usize) -> BoolGrid2D {
+        BoolGrid2D {
+            array: vec![false; width * height],
+            width,
+            height,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub(crate) fn get_index(&self, x: usize, y: usize) -> usize {
+        x + y * self.width
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        let index = self.get_index(x, y);
+        self.array[index]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, val: bool) {
+        let index = self.get_index(x, y);
+        self.array[index] = val;
+    }
+
+    /// Coordinates of every live cell, in row-major order.
+    pub fn live_cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.cells()
+            .filter(|&(_, _, alive)| alive)
+            .map(|(x, y, _)| (x, y))
+    }
+
+    /// Every cell as `(x, y, alive)`, in row-major order.
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize, bool)> + '_ {
+        let width = self.width;
+        self.array
+            .iter()
+            .enumerate()
+            .map(move |(index, &alive)| (index % width, index / width, alive))
+    }
+
+    /// Number of live cells.
+    pub fn population(&self) -> usize {
+        self.array.iter().filter(|&&alive| alive).count()
+    }
+
+    /// The average position of the live cells, or `None` if there are none.
+    pub fn centroid(&self) -> Option<(f32, f32)> {
+        let (mut sum_x, mut sum_y, mut count) = (0.0, 0.0, 0);
+        for (x, y) in self.live_cells() {
+            sum_x += x as f32;
+            sum_y += y as f32;
+            count += 1;
+        }
+
+        (count > 0).then(|| (sum_x / count as f32, sum_y / count as f32))
+    }
+
+    /// The smallest piece of the board holding every live cell, or `None` if
+    /// there are none.
+    pub fn crop_to_content(&self) -> Option<BoolGrid2D> {
+        let ((left, top), (right, bottom)) = self.content_bounds()?;
+
+        let mut cropped = BoolGrid2D::new(right + 1 - left, bottom + 1 - top);
+        for y in top..=bottom {
+            for x in left..=right {
+                cropped.set(x - left, y - top, self.get(x, y));
+            }
+        }
+        Some(cropped)
+    }
+
+    /// The top-left and bottom-right corners, inclusive, of the smallest
+    /// rectangle holding every live cell, or `None` if there are none.
+    pub fn content_bounds(&self) -> Option<((usize, usize), (usize, usize))> {
+        let mut cells = self.live_cells();
+        let (x, y) = cells.next()?;
+        // Live cells come in row-major order, so the first is on the top row
+        // and the last on the bottom row.
+        let top = y;
+        let (mut left, mut right, mut bottom) = (x, x, y);
+        for (x, y) in cells {
+            left = left.min(x);
+            right = right.max(x);
+            bottom = y;
+        }
+        Some(((left, top), (right, bottom)))
+    }
+
+    /// The board as rows of `O` (alive) and `.` (dead), one line per row.
+    pub fn to_plaintext(&self) -> String {
+        let mut text = String::with_capacity((self.width + 1) * self.height);
+        for row in self.array.chunks(self.width) {
+            text.extend(row.iter().map(|&alive| if alive { 'O' } else { '.' }));
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Reads the plaintext `.cells` format: rows of `.` for dead cells and
+    /// `O` (or `*`) for live ones, with `!` starting a comment line. Rows
+    /// may be of different lengths, and the board is as wide as the
+    /// longest. Windows line endings and trailing whitespace are ignored,
+    /// but leading dots are cells, so lines are only trimmed at the end.
+    pub fn from_plaintext(text: &str) -> Result<BoolGrid2D, InvalidPlaintext> {
+        let mut rows = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            if line.starts_with('!') {
+                continue;
+            }
+
+            let mut row = Vec::new();
+            for character in line.trim_end().chars() {
+                match character {
+                    '.' => row.push(false),
+                    'O' | '*' => row.push(true),
+                    character => {
+                        return Err(InvalidPlaintext {
+                            line: index + 1,
+                            character,
+                        })
+                    }
+                }
+            }
+            rows.push(row);
+        }
+
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let mut grid = BoolGrid2D::new(width, rows.len());
+        for (y, row) in rows.iter().enumerate() {
+            for (x, &alive) in row.iter().enumerate() {
+                grid.set(x, y, alive);
+            }
+        }
+        Ok(grid)
+    }
+
+    /// The live cells' coordinates as CSV, under an `x,y` header row.
+    pub fn to_csv(&self) -> String {
+        let mut text = "x,y\n".to_string();
+        for (x, y) in self.live_cells() {
+            text += &format!("{},{}\n", x, y);
+        }
+        text
+    }
+
+    /// Reads `x,y` rows of live cells, like `to_csv` writes, into a board
+    /// just large enough to hold them at their coordinates. A header row,
+    /// blank lines and whitespace around values are allowed.
+    pub fn from_csv(text: &str) -> Result<BoolGrid2D, InvalidCsv> {
+        let mut cells = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || (index == 0 && line.eq_ignore_ascii_case("x,y")) {
+                continue;
+            }
+
+            let cell = line.split_once(',').and_then(|(x, y)| {
+                Some((
+                    x.trim().parse::<usize>().ok()?,
+                    y.trim().parse::<usize>().ok()?,
+                ))
+            });
+            cells.push(cell.ok_or_else(|| InvalidCsv {
+                line: index + 1,
+                text: line.to_string(),
+            })?);
+        }
+
+        let width = cells.iter().map(|&(x, _)| x + 1).max().unwrap_or(0);
+        let height = cells.iter().map(|&(_, y)| y + 1).max().unwrap_or(0);
+        let mut grid = BoolGrid2D::new(width, height);
+        for (x, y) in cells {
+            grid.set(x, y, true);
+        }
+        Ok(grid)
+    }
+
+    /// Labels every live cell with the index of its 8-connected cluster, in
+    /// order of each cluster's first cell in row-major order. Dead cells are
+    /// `None`. Edges never wrap.
+    pub fn component_ids(&self) -> Vec<Option<usize>> {
+        let mut ids = vec![None; self.array.len()];
+        let mut next_id = 0;
+
+        for start in 0..self.array.len() {
+            if !self.array[start] || ids[start].is_some() {
+                continue;
+            }
+
+            let mut stack = vec![start];
+            ids[start] = Some(next_id);
+
+            while let Some(index) = stack.pop() {
+                let (x, y) = (index % self.width, index / self.width);
+
+                for ny in y.saturating_sub(1)..=(y + 1).min(self.height - 1) {
+                    for nx in x.saturating_sub(1)..=(x + 1).min(self.width - 1) {
+                        let neighbor = self.get_index(nx, ny);
+                        if self.array[neighbor] && ids[neighbor].is_none() {
+                            ids[neighbor] = Some(next_id);
+                            stack.push(neighbor);
+                        }
+                    }
+                }
+            }
+
+            next_id += 1;
+        }
+
+        ids
+    }
+
+    /// Moves every cell by `(dx, dy)`. Cells pushed past an edge are lost or
+    /// wrapped around, depending on `boundary`.
+    pub fn shift(&mut self, dx: isize, dy: isize, boundary: Boundary) {
+        let mut shifted = vec![false; self.array.len()];
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if !self.get(x, y) {
+                    continue;
+                }
+
+                if let Some((nx, ny)) = boundary.offset(x, y, dx, dy, self.width, self.height) {
+                    shifted[self.get_index(nx, ny)] = true;
+                }
+            }
+        }
+
+        self.array = shifted;
+    }
+
+    /// A mirror image of the board along `axis`.
+    pub fn flipped(&self, axis: Axis) -> BoolGrid2D {
+        let mut flipped = BoolGrid2D::new(self.width, self.height);
+        for (x, y) in self.live_cells() {
+            match axis {
+                Axis::Horizontal => flipped.set(self.width - 1 - x, y, true),
+                Axis::Vertical => flipped.set(x, self.height - 1 - y, true),
+            }
+        }
+        flipped
+    }
+
+    /// The board turned clockwise by `quarter_turns` right angles. Odd turns
+    /// swap the width and height.
+    pub fn rotated(&self, quarter_turns: u8) -> BoolGrid2D {
+        let (width, height) = (self.width, self.height);
+        let mut rotated = match quarter_turns % 4 {
+            1 | 3 => BoolGrid2D::new(height, width),
+            _ => BoolGrid2D::new(width, height),
+        };
+
+        for (x, y) in self.live_cells() {
+            let (rx, ry) = match quarter_turns % 4 {
+                1 => (height - 1 - y, x),
+                2 => (width - 1 - x, height - 1 - y),
+                3 => (y, width - 1 - x),
+                _ => (x, y),
+            };
+            rotated.set(rx, ry, true);
+        }
+        rotated
+    }
+
+    /// Brings every live cell of `other` to life here too.
+    ///
+    /// Panics if the boards aren't the same size.
+    pub fn union_with(&mut self, other: &BoolGrid2D) {
+        assert_eq!(
+            (self.width, self.height),
+            (other.width, other.height),
+            "boards of different sizes can't be merged"
+        );
+        for (cell, &alive) in self.array.iter_mut().zip(&other.array) {
+            *cell |= alive;
+        }
+    }
+
+    /// Replaces every cell with `op` applied to it and the same cell of
+    /// `other`.
+    ///
+    /// Panics if the boards aren't the same size.
+    pub fn combine(&mut self, other: &BoolGrid2D, op: BoolOp) {
+        assert_eq!(
+            (self.width, self.height),
+            (other.width, other.height),
+            "boards of different sizes can't be combined"
+        );
+        for (cell, &theirs) in self.array.iter_mut().zip(&other.array) {
+            *cell = op.apply(*cell, theirs);
+        }
+    }
+
+    /// Sets the live cells of `pattern` with its top-left corner at `(x, y)`.
+    /// Cells that would land outside the grid are dropped.
+    pub fn stamp(&mut self, pattern: &BoolGrid2D, x: isize, y: isize) {
+        for (px, py) in pattern.live_cells() {
+            let (bx, by) = (x + px as isize, y + py as isize);
+            if (0..self.width as isize).contains(&bx) && (0..self.height as isize).contains(&by) {
+                self.set(bx as usize, by as usize, true);
+            }
+        }
+    }
+
+    /// The largest rectangle of dead cells by area, as its top-left and
+    /// bottom-right cells, or `None` if every cell is alive. Each row keeps
+    /// a count of the dead cells running up from it in every column, and a
+    /// stack finds the widest rectangle under those heights, so the whole
+    /// search takes time proportional to the number of cells.
+    pub fn largest_empty_rectangle(&self) -> Option<((usize, usize), (usize, usize))> {
+        let mut heights = vec![0; self.width];
+        let mut stack: Vec<usize> = Vec::with_capacity(self.width);
+        // The area, top-left and bottom-right of the largest so far.
+        let mut best = None;
+
+        for y in 0..self.height {
+            for (x, height) in heights.iter_mut().enumerate() {
+                *height = if self.get(x, y) { 0 } else { *height + 1 };
+            }
+
+            // A zero height past the last column empties the stack.
+            stack.clear();
+            for x in 0..=self.width {
+                let height = heights.get(x).copied().unwrap_or(0);
+                while let Some(&top) = stack.last() {
+                    if heights[top] < height {
+                        break;
+                    }
+                    stack.pop();
+                    let left = stack.last().map_or(0, |&column| column + 1);
+                    let area = heights[top] * (x - left);
+                    if area > 0 && best.is_none_or(|(largest, _, _)| area > largest) {
+                        best = Some((area, (left, y + 1 - heights[top]), (x - 1, y)));
+                    }
+                }
+                stack.push(x);
+            }
+        }
+
+        best.map(|(_, top_left, bottom_right)| (top_left, bottom_right))
+    }
+
+    /// How far `resize` moves every cell to make a `width` by `height`
+    /// board.
+    pub fn resize_offset(&self, width: usize, height: usize, anchor: Anchor) -> (isize, isize) {
+        match anchor {
+            Anchor::TopLeft => (0, 0),
+            Anchor::Center => (
+                (width as isize - self.width as isize) / 2,
+                (height as isize - self.height as isize) / 2,
+            ),
+        }
+    }
+
+    /// A `width` by `height` copy of the board placed by `anchor`. Only the
+    /// overlapping region is copied, so shrinking crops the cells past the
+    /// new edges and growing leaves the new area dead.
+    pub fn resize(&self, width: usize, height: usize, anchor: Anchor) -> BoolGrid2D {
+        let (x, y) = self.resize_offset(width, height, anchor);
+        let mut resized = BoolGrid2D::new(width, height);
+        resized.stamp(self, x, y);
+        resized
+    }
+
+    /// A copy of the board with `margin` dead cells added on every side.
+    pub fn grow(&self, margin: usize) -> BoolGrid2D {
+        let mut grown = BoolGrid2D::new(self.width + 2 * margin, self.height + 2 * margin);
+        for (x, y) in self.live_cells() {
+            grown.set(x + margin, y + margin, true);
+        }
+        grown
+    }
+
+    /// How many cells differ between the two boards.
+    ///
+    /// Panics if the boards aren't the same size.
+    pub fn hamming_distance(&self, other: &BoolGrid2D) -> usize {
+        assert_eq!(
+            (self.width, self.height),
+            (other.width, other.height),
+            "boards of different sizes can't be compared"
+        );
+        self.array
+            .iter()
+            .zip(&other.array)
+            .filter(|(a, b)| a != b)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    fn grid_with(width: usize, height: usize, cells: &[(usize, usize)]) -> BoolGrid2D {
+        let mut grid = BoolGrid2D::new(width, height);
+        for &(x, y) in cells {
+            grid.set(x, y, true);
+        }
+        grid
+    }
+
+    fn cells(grid: &BoolGrid2D) -> Vec<(usize, usize)> {
+        grid.live_cells().collect()
+    }
+
+    /// One B3/S23 generation with neighbors found through `boundary`.
+    fn step(grid: &BoolGrid2D, boundary: Boundary) -> BoolGrid2D {
+        let mut next = BoolGrid2D::new(grid.width, grid.height);
+        for (x, y, alive) in grid.cells() {
+            let neighbors = SQUARE_NEIGHBORS
+                .iter()
+                .filter_map(|&(dx, dy)| boundary.offset(x, y, dx, dy, grid.width, grid.height))
+                .filter(|&(nx, ny)| grid.get(nx, ny))
+                .count();
+            next.set(x, y, neighbors == 3 || (alive && neighbors == 2));
+        }
+        next
+    }
+
+    /// Runs a down-right glider from `(x, y)` for `generations` and returns
+    /// the heading of whatever glider is left.
+    fn glider_heading_after(
+        boundary: Boundary,
+        x: usize,
+        y: usize,
+        generations: usize,
+    ) -> Option<(i32, i32)> {
+        let glider = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+        let mut grid = grid_with(16, 16, &glider.map(|(dx, dy)| (x + dx, y + dy)));
+        for _ in 0..generations {
+            grid = step(&grid, boundary);
+        }
+        let live: Vec<(i32, i32)> = grid
+            .live_cells()
+            .map(|(x, y)| (x as i32, y as i32))
+            .collect();
+        crate::census::glider_heading(&live)
+    }
+
+    #[test]
+    fn shift_moves_cells_in_every_direction() {
+        for boundary in [Boundary::Dead, Boundary::Toroidal] {
+            for (dx, dy, moved) in [
+                (1, 0, (3, 2)),
+                (-1, 0, (1, 2)),
+                (0, -1, (2, 1)),
+                (0, 1, (2, 3)),
+            ] {
+                let mut grid = grid_with(5, 5, &[(2, 2)]);
+                grid.shift(dx, dy, boundary);
+                assert_eq!(
+                    cells(&grid),
+                    [moved],
+                    "shift by {},{} on {}",
+                    dx,
+                    dy,
+                    boundary
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn shift_drops_cells_past_a_dead_edge() {
+        for (dx, dy) in [(1, 0), (0, -1)] {
+            let mut grid = grid_with(5, 5, &[(4, 0)]);
+            grid.shift(dx, dy, Boundary::Dead);
+            assert_eq!(cells(&grid), [], "shift by {},{}", dx, dy);
+        }
+        for (dx, dy) in [(-1, 0), (0, 1)] {
+            let mut grid = grid_with(5, 5, &[(0, 4)]);
+            grid.shift(dx, dy, Boundary::Dead);
+            assert_eq!(cells(&grid), [], "shift by {},{}", dx, dy);
+        }
+    }
+
+    #[test]
+    fn shift_wraps_cells_past_a_toroidal_edge() {
+        for (start, dx, dy, wrapped) in [
+            ((4, 0), 1, 0, (0, 0)),
+            ((4, 0), 0, -1, (4, 4)),
+            ((0, 4), -1, 0, (4, 4)),
+            ((0, 4), 0, 1, (0, 0)),
+        ] {
+            let mut grid = grid_with(5, 5, &[start]);
+            grid.shift(dx, dy, Boundary::Toroidal);
+            assert_eq!(cells(&grid), [wrapped], "shift by {},{}", dx, dy);
+        }
+    }
+
+    #[test]
+    fn component_ids_label_each_blinker_once() {
+        let grid = grid_with(8, 8, &[(1, 1), (2, 1), (3, 1), (5, 4), (5, 5), (5, 6)]);
+        let ids = grid.component_ids();
+
+        let labels: HashSet<usize> = ids.iter().flatten().copied().collect();
+        assert_eq!(labels, HashSet::from([0, 1]));
+        assert_eq!(ids.iter().flatten().count(), 6);
+        for (x, y) in [(1, 1), (2, 1), (3, 1)] {
+            assert_eq!(ids[grid.get_index(x, y)], Some(0));
+        }
+        for (x, y) in [(5, 4), (5, 5), (5, 6)] {
+            assert_eq!(ids[grid.get_index(x, y)], Some(1));
+        }
+    }
+
+    /// `(0, 0)` and `(1, 0)` combined with `(1, 0)` and `(2, 0)` by `op`.
+    fn combined(op: BoolOp) -> Vec<(usize, usize)> {
+        let mut grid = grid_with(4, 1, &[(0, 0), (1, 0)]);
+        grid.combine(&grid_with(4, 1, &[(1, 0), (2, 0)]), op);
+        cells(&grid)
+    }
+
+    #[test]
+    #[should_panic(expected = "boards of different sizes")]
+    fn union_with_rejects_a_board_of_another_size() {
+        let mut grid = grid_with(4, 2, &[]);
+        grid.union_with(&grid_with(2, 4, &[(1, 3)]));
+    }
+
+    #[test]
+    fn combine_with_and() {
+        assert_eq!(combined(BoolOp::And), [(1, 0)]);
+    }
+
+    #[test]
+    fn combine_with_or() {
+        assert_eq!(combined(BoolOp::Or), [(0, 0), (1, 0), (2, 0)]);
+    }
+
+    #[test]
+    fn combine_with_xor() {
+        assert_eq!(combined(BoolOp::Xor), [(0, 0), (2, 0)]);
+    }
+
+    #[test]
+    fn combine_with_and_not() {
+        assert_eq!(combined(BoolOp::AndNot), [(0, 0)]);
+    }
+
+    const GLIDER_CELLS: &str = "!Name: Glider\n.O.\n..O\nOOO\n";
+
+    #[test]
+    fn from_plaintext_reads_windows_line_endings() {
+        let crlf = GLIDER_CELLS.replace('\n', "\r\n");
+        assert_eq!(
+            BoolGrid2D::from_plaintext(&crlf),
+            BoolGrid2D::from_plaintext(GLIDER_CELLS)
+        );
+        let grid = BoolGrid2D::from_plaintext(&crlf).unwrap();
+        assert_eq!((grid.width, grid.height), (3, 3));
+        assert_eq!(cells(&grid), [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn from_plaintext_ignores_trailing_spaces() {
+        let padded = "!Name: Glider  \n.O.   \n..O\t\nOOO \r\n";
+        let grid = BoolGrid2D::from_plaintext(padded).unwrap();
+        assert_eq!(Ok(grid), BoolGrid2D::from_plaintext(GLIDER_CELLS));
+
+        // Spaces inside a row aren't cells, though.
+        assert_eq!(
+            BoolGrid2D::from_plaintext(".O.\nO O\n"),
+            Err(InvalidPlaintext {
+                line: 2,
+                character: ' '
+            })
+        );
+    }
+
+    #[test]
+    fn hexagonal_boards_wrap_only_with_even_heights() {
+        let wrapping = [
+            Boundary::Toroidal,
+            Boundary::KleinBottle,
+            Boundary::ProjectivePlane,
+        ];
+        for boundary in wrapping {
+            assert!(GridTopology::Hexagonal.fits(boundary, 10));
+            assert!(!GridTopology::Hexagonal.fits(boundary, 9), "{}", boundary);
+            assert!(GridTopology::Square.fits(boundary, 9));
+        }
+        assert!(GridTopology::Hexagonal.fits(Boundary::Dead, 9));
+    }
+
+    #[test]
+    fn glider_comes_back_mirrored_across_a_twisted_seam() {
+        // Off the bottom edge: both twisted boundaries flip it left to right.
+        for boundary in [Boundary::KleinBottle, Boundary::ProjectivePlane] {
+            assert_eq!(
+                glider_heading_after(boundary, 6, 11, 24),
+                Some((-1, 1)),
+                "{}",
+                boundary
+            );
+        }
+
+        // Off the right edge: only the projective plane flips it top to bottom.
+        assert_eq!(
+            glider_heading_after(Boundary::KleinBottle, 11, 6, 24),
+            Some((1, 1))
+        );
+        assert_eq!(
+            glider_heading_after(Boundary::ProjectivePlane, 11, 6, 24),
+            Some((1, -1))
+        );
+    }
+}
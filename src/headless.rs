@@ -0,0 +1,128 @@
+use std::{collections::VecDeque, fmt};
+
+use conways_gol::{game::Game, grid::BoolGrid2D};
+
+/// Something that ends a headless run early.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StopCondition {
+    PopulationBelow(usize),
+    PopulationAbove(usize),
+    /// The board came back to a state it was in at most `--max-period`
+    /// generations ago.
+    Stable,
+}
+
+impl fmt::Display for StopCondition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StopCondition::PopulationBelow(population) => {
+                write!(f, "population below {}", population)
+            }
+            StopCondition::PopulationAbove(population) => {
+                write!(f, "population above {}", population)
+            }
+            StopCondition::Stable => write!(f, "board is stable"),
+        }
+    }
+}
+
+/// Spots boards that repeat, by keeping the last `max_period` of them,
+/// oldest first. Memory is bounded by that many boards.
+pub struct PeriodDetector {
+    previous: VecDeque<BoolGrid2D>,
+    max_period: usize,
+}
+
+impl PeriodDetector {
+    pub fn new(max_period: usize) -> PeriodDetector {
+        PeriodDetector {
+            previous: VecDeque::with_capacity(max_period),
+            max_period,
+        }
+    }
+
+    /// Records `board` as the newest, returning the period it repeats with
+    /// if it matches one of the boards before it: 1 for a still life, 2 for
+    /// a blinker and so on. The shortest matching period wins.
+    pub fn observe(&mut self, board: &BoolGrid2D) -> Option<usize> {
+        let period = self
+            .previous
+            .iter()
+            .rev()
+            .position(|previous| previous == board)
+            .map(|age| age + 1);
+
+        if self.previous.len() == self.max_period {
+            self.previous.pop_front();
+        }
+        self.previous.push_back(board.clone());
+        period
+    }
+}
+
+/// Runs `generations` generations without drawing anything, to get past a
+/// pattern's uninteresting start before the window opens. `generation`
+/// counts them like any others.
+pub fn burn_in(game: &mut Game, generations: u64) {
+    for _ in 0..generations {
+        game.update();
+    }
+}
+
+/// Runs `game` until any of `conditions` holds or `max_generations` have
+/// passed, then reports the outcome and prints the board. `Stable` looks for
+/// periods up to `max_period`. Returns whether a condition was met.
+pub fn run(
+    game: &mut Game,
+    conditions: &[StopCondition],
+    max_generations: u64,
+    max_period: usize,
+) -> bool {
+    let mut detector = PeriodDetector::new(max_period);
+    detector.observe(&game.grid);
+    let mut met = None;
+    let mut period = None;
+
+    while game.generation < max_generations {
+        game.update();
+        period = detector.observe(&game.grid);
+
+        let population = game.grid.population();
+        met = conditions
+            .iter()
+            .copied()
+            .find(|condition| match *condition {
+                StopCondition::PopulationBelow(threshold) => population < threshold,
+                StopCondition::PopulationAbove(threshold) => population > threshold,
+                StopCondition::Stable => period.is_some(),
+            });
+
+        if met.is_some() {
+            break;
+        }
+    }
+
+    match (met, period) {
+        (Some(StopCondition::Stable), Some(period @ 2..)) => println!(
+            "Stopped at generation {}: board repeats with period {}",
+            game.generation, period
+        ),
+        (Some(condition), _) => {
+            println!("Stopped at generation {}: {}", game.generation, condition)
+        }
+        (None, _) if conditions.is_empty() => {
+            println!("Ran {} generations", game.generation)
+        }
+        (None, _) => println!(
+            "Reached generation {} without any stop condition holding",
+            game.generation
+        ),
+    }
+    println!("Population: {}", game.grid.population());
+    if game.count_escaped_gliders {
+        println!("Escaped gliders: {}", game.escaped_gliders);
+    }
+    print!("{}", game.grid.to_plaintext());
+
+    met.is_some() || conditions.is_empty()
+}
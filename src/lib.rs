@@ -0,0 +1,15 @@
+pub mod ash;
+pub mod bindings;
+pub mod census;
+pub mod formula;
+pub mod framebuffer;
+pub mod game;
+pub mod grid;
+pub mod library;
+pub mod overlay;
+pub mod packed;
+pub mod rle;
+pub mod rng;
+pub mod rule;
+pub mod rule_table;
+pub mod timing;
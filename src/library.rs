@@ -0,0 +1,111 @@
+use crate::grid::BoolGrid2D;
+
+/// A named pattern that ships with the game, so common objects can be put
+/// on the board without a file.
+pub struct LibraryPattern {
+    pub name: &'static str,
+    /// The cells, drawn with `O` for live cells like the census catalog.
+    rows: &'static [&'static str],
+    /// For oscillators, how many generations one cycle takes.
+    pub period: Option<usize>,
+    /// Names for some or all of an oscillator's phases, starting with the
+    /// one in `rows`. Phases past the end only get a number.
+    pub phase_labels: &'static [&'static str],
+}
+
+impl LibraryPattern {
+    pub fn grid(&self) -> BoolGrid2D {
+        let width = self.rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        let mut grid = BoolGrid2D::new(width, self.rows.len());
+        for (y, row) in self.rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                grid.set(x, y, c == 'O');
+            }
+        }
+        grid
+    }
+
+    /// Which phase an oscillator is in `generations` after it was placed,
+    /// like "pulsar phase 1/3", or `None` if it isn't an oscillator.
+    pub fn phase_label(&self, generations: u64) -> Option<String> {
+        let period = self.period?;
+        let phase = (generations % period as u64) as usize;
+
+        let mut label = format!("{} phase {}/{}", self.name, phase + 1, period);
+        if let Some(name) = self.phase_labels.get(phase) {
+            label.push_str(&format!(" ({})", name));
+        }
+        Some(label)
+    }
+}
+
+pub const LIBRARY: &[LibraryPattern] = &[
+    LibraryPattern {
+        name: "block",
+        rows: &["OO", "OO"],
+        period: None,
+        phase_labels: &[],
+    },
+    LibraryPattern {
+        name: "glider",
+        rows: &[".O.", "..O", "OOO"],
+        period: None,
+        phase_labels: &[],
+    },
+    LibraryPattern {
+        name: "blinker",
+        rows: &["OOO"],
+        period: Some(2),
+        phase_labels: &["horizontal", "vertical"],
+    },
+    LibraryPattern {
+        name: "toad",
+        rows: &[".OOO", "OOO."],
+        period: Some(2),
+        phase_labels: &["flat", "spread"],
+    },
+    LibraryPattern {
+        name: "beacon",
+        rows: &["OO..", "OO..", "..OO", "..OO"],
+        period: Some(2),
+        phase_labels: &["lit", "dark"],
+    },
+    LibraryPattern {
+        name: "pulsar",
+        rows: &[
+            "..OOO...OOO..",
+            ".............",
+            "O....O.O....O",
+            "O....O.O....O",
+            "O....O.O....O",
+            "..OOO...OOO..",
+            ".............",
+            "..OOO...OOO..",
+            "O....O.O....O",
+            "O....O.O....O",
+            "O....O.O....O",
+            ".............",
+            "..OOO...OOO..",
+        ],
+        period: Some(3),
+        phase_labels: &[],
+    },
+    LibraryPattern {
+        name: "pentadecathlon",
+        rows: &["..O....O..", "OO.OOOO.OO", "..O....O.."],
+        period: Some(15),
+        phase_labels: &[],
+    },
+];
+
+/// The library pattern called `name`, in any case.
+pub fn find(name: &str) -> Option<&'static LibraryPattern> {
+    LIBRARY
+        .iter()
+        .find(|pattern| pattern.name.eq_ignore_ascii_case(name))
+}
+
+/// Every pattern's name, for listing the choices.
+pub fn names() -> Vec<&'static str> {
+    LIBRARY.iter().map(|pattern| pattern.name).collect()
+}
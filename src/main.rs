@@ -1,232 +1,796 @@
+mod autosave;
+mod bench;
+mod capture;
+mod cli;
+mod compare;
+mod equilibrium;
+mod headless;
+mod population_log;
+mod recording;
+mod reveal;
+
+use std::{cell::RefCell, env, fs, panic, process, rc::Rc, time::Duration};
+
+use autosave::Autosave;
+use capture::Capture;
+use cli::{Command, Options};
+use conways_gol::{
+    game::{Game, GameConfig, PlacementMode, UpdateMode},
+    grid::{BoolGrid2D, GridTopology},
+    overlay, rle,
+    rule::Rule,
+    rule_table::RuleTable,
+    timing::TickScheduler,
+};
+use equilibrium::EquilibriumAlert;
+use headless::PeriodDetector;
+use population_log::PopulationLog;
+use reveal::Reveal;
 use sfml::{
-    graphics::{Color, RectangleShape, RenderTarget, RenderWindow, Shape, Transformable, View},
-    system::{Clock, Time, Vector2, Vector2f, Vector2i},
-    window::{ContextSettings, Event, Key, Style},
+    graphics::{Color, FloatRect, Image, RenderTarget, RenderWindow, View},
+    system::{Clock, Time, Vector2f, Vector2i},
+    window::{mouse::Wheel, ContextSettings, Event, Style},
 };
 
-struct BoolGrid2D {
-    array: Vec<bool>,
-    width: usize,
-    height: usize,
+/// How far the view moves toward the live cells each frame while following,
+/// as a fraction of the remaining distance.
+const FOLLOW_SMOOTHING: f32 = 0.1;
+
+const FRAME_RATE: u32 = 30;
+/// The frame rate while a drag is painting cells, so the stroke keeps up
+/// with the pointer on screen.
+const PAINTING_FRAME_RATE: u32 = 60;
+
+/// How many overlapping cells `--stamp` lists before just counting the rest.
+const MAX_LISTED_CONFLICTS: usize = 10;
+
+/// How much one notch of the scroll wheel scales the view by.
+const ZOOM_STEP: f32 = 1.1;
+/// The narrowest the view may get, in cells, and the widest, in boards.
+const MIN_VIEW_CELLS: f32 = 2.0;
+const MAX_VIEW_BOARDS: f32 = 8.0;
+
+/// Wall-clock time per frame that may be spent running catch-up ticks.
+const FRAME_TICK_BUDGET: Time = Time::milliseconds(10);
+
+/// Scales `view` by `factor` about `anchor`, a point in world coordinates.
+/// Moving the center toward the anchor by the same factor keeps the anchor
+/// on the same pixel, whatever the viewport. The factor is cut short so the
+/// view stays between `min_width` and `max_width` wide.
+fn zoom_view(view: &mut View, anchor: Vector2f, factor: f32, min_width: f32, max_width: f32) {
+    let size = view.size();
+    let factor = (size.x * factor).clamp(min_width, max_width) / size.x;
+
+    view.set_size(size * factor);
+    view.set_center(anchor + (view.center() - anchor) * factor);
 }
 
-impl BoolGrid2D {
-    fn new(width: usize, height: usize) -> BoolGrid2D {
-        BoolGrid2D {
-            array: vec![false; width * height],
-            width,
-            height,
+/// The viewport that shows all of a `view_size` world area at its true
+/// aspect ratio, centered in the window, with bars filling the rest.
+fn letterbox_viewport(window_width: u32, window_height: u32, view_size: Vector2f) -> FloatRect {
+    let window_ratio = window_width as f32 / window_height as f32;
+    let view_ratio = view_size.x / view_size.y;
+
+    if window_ratio > view_ratio {
+        let width = view_ratio / window_ratio;
+        FloatRect::new((1.0 - width) / 2.0, 0.0, width, 1.0)
+    } else {
+        let height = window_ratio / view_ratio;
+        FloatRect::new(0.0, (1.0 - height) / 2.0, 1.0, height)
+    }
+}
+
+/// Letterboxes `view` to its own size in a window of `window_width` by
+/// `window_height`, of which each board gets half the width when comparing.
+fn letterbox(view: &mut View, window_width: u32, window_height: u32, comparing: bool) {
+    let pane_width = if comparing {
+        window_width / 2
+    } else {
+        window_width
+    };
+    let viewport = letterbox_viewport(pane_width, window_height, view.size());
+    view.set_viewport(&viewport);
+}
+
+/// A board with the look and rule asked for on the command line.
+fn new_game(options: &Options) -> Game {
+    let config = GameConfig {
+        width: options.board_width,
+        height: options.board_height,
+        rule: options.rule.unwrap_or(match options.topology {
+            GridTopology::Square => Rule::CONWAY,
+            GridTopology::Hexagonal => Rule::HEX_LIFE,
+        }),
+        topology: options.topology,
+        boundary: options.boundary,
+        cell_size: options.cell_size,
+        dead_cell_color: options.dead_cell_color,
+        ..GameConfig::default()
+    };
+    let mut game = Game::try_new(config).unwrap_or_else(|error| {
+        eprintln!("Couldn't create the board: {}", error);
+        process::exit(2);
+    });
+
+    game.cell_padding = options.cell_padding;
+    game.y_up = options.y_up;
+    game.tile_spacing = options.tile_spacing;
+    game.trail_decay = options.trail_decay;
+    game.activity_window = options.activity_window;
+    game.activity_decay = options.activity_decay;
+    game.noise_rate = options.noise_rate;
+    game.mutation_interval = options.mutation_interval;
+    game.symmetry = options.symmetry;
+    game.symmetry_axes = options.symmetry_axes;
+    game.apply_rle_rules = options.apply_rle_rules;
+    game.log_violations = options.log_violations;
+    game.count_escaped_gliders = options.count_escapes;
+    if options.async_scan {
+        game.update_mode = UpdateMode::AsyncScan;
+    }
+    if let Some(path) = &options.palette_path {
+        game.palette = Image::from_file(path);
+        if game.palette.is_none() {
+            eprintln!("Couldn't load palette image {}, using plain colors", path);
         }
     }
 
-    fn get_index(&self, x: usize, y: usize) -> usize {
-        x + y * self.width
+    game
+}
+
+/// Advances both boards by a generation, returning how long the main
+/// board spent stepping.
+fn step(game: &mut Game, comparison: &mut Option<Game>) -> Time {
+    if enforce_generation_cap(game, comparison) {
+        return Time::ZERO;
     }
 
-    fn get(&self, x: usize, y: usize) -> bool {
-        let index = self.get_index(x, y);
-        self.array[index]
+    game.update();
+    if let Some(other) = comparison {
+        other.update();
     }
+    enforce_generation_cap(game, comparison);
+    game.step_time
+}
 
-    fn set(&mut self, x: usize, y: usize, val: bool) {
-        let index = self.get_index(x, y);
-        self.array[index] = val;
+/// Pauses both boards if the main one has run into `max_generation`, so
+/// resuming stops again straight away. Returns whether it's at the cap.
+fn enforce_generation_cap(game: &mut Game, comparison: &mut Option<Game>) -> bool {
+    let Some(cap) = game.max_generation.filter(|_| game.at_generation_cap()) else {
+        return false;
+    };
+
+    if !game.paused {
+        println!(
+            "Reached the cap of {} generations at generation {}, clear the board to go on",
+            cap, game.generation
+        );
     }
+    for game in std::iter::once(game).chain(comparison.as_mut()) {
+        game.paused = true;
+    }
+    true
 }
 
-struct Game {
-    grid: BoolGrid2D,
-    simulation_grid: BoolGrid2D,
-    paused: bool,
-    cell_size: Vector2f,
-    cell_color: Color,
+/// A pattern file as `--load` and `--combine` use it.
+struct PatternFile {
+    pattern: BoolGrid2D,
+    /// The rule its header names.
+    rule: Option<Rule>,
+    /// The top-left corner its `#P` or `#R` line gives.
+    position: Option<(isize, isize)>,
 }
 
-impl Game {
-    fn new(width: usize, height: usize) -> Game {
-        Game {
-            grid: BoolGrid2D::new(width, height),
-            simulation_grid: BoolGrid2D::new(width, height),
-            paused: false,
-            cell_size: Vector2f::new(10.0, 10.0),
-            cell_color: Color::rgb(255, 255, 255),
-        }
+/// Warns on stderr when some of `pattern`'s live cells would land off the
+/// board with its top-left corner at `x, y`, since stamping drops them.
+fn warn_if_clipped(game: &Game, path: &str, pattern: &BoolGrid2D, x: isize, y: isize) {
+    let (width, height) = (game.grid.width() as isize, game.grid.height() as isize);
+    let clipped = pattern
+        .live_cells()
+        .map(|(px, py)| (x + px as isize, y + py as isize))
+        .filter(|&(cx, cy)| cx < 0 || cy < 0 || cx >= width || cy >= height)
+        .count();
+    if clipped > 0 {
+        eprintln!(
+            "{} doesn't fit on the {}x{} board at {},{}: {} live cells past the edge are dropped",
+            path, width, height, x, y, clipped
+        );
     }
+}
 
-    fn get_cell_below_position(&self, position: Vector2f) -> Vector2<usize> {
-        Vector2::new(
-            (position.x / self.cell_size.x).floor() as usize,
-            (position.y / self.cell_size.y).floor() as usize,
-        )
+/// Reads RLE, or a CSV of `x,y` rows for paths ending in `.csv`. CSV has
+/// no header to name a rule, and its position is where its cells start.
+fn read_pattern(path: &str) -> Result<PatternFile, String> {
+    let text = fs::read_to_string(path).map_err(|error| error.to_string())?;
+    // Editors on Windows often start files with a byte order mark, which
+    // no format expects and trimming doesn't remove.
+    let text = text.strip_prefix('\u{feff}').unwrap_or(&text);
+    let extension = path.to_ascii_lowercase();
+
+    if extension.ends_with(".cells") {
+        // Not cropped: leading dead rows and columns are part of the pattern.
+        let pattern = BoolGrid2D::from_plaintext(text).map_err(|error| error.to_string())?;
+        return Ok(PatternFile {
+            pattern,
+            rule: None,
+            position: None,
+        });
     }
-
-    fn toggle_cell(&mut self, position: Vector2<usize>) {
-        self.grid.set(
-            position.x,
-            position.y,
-            !self.grid.get(position.x, position.y),
-        );
+    if extension.ends_with(".csv") {
+        let grid = BoolGrid2D::from_csv(text).map_err(|error| error.to_string())?;
+        let (Some(pattern), Some((position, _))) = (grid.crop_to_content(), grid.content_bounds())
+        else {
+            return Err("no live cells listed".to_string());
+        };
+        return Ok(PatternFile {
+            pattern,
+            rule: None,
+            position: Some((position.0 as isize, position.1 as isize)),
+        });
     }
 
-    fn get_neighbors_count(&self, x: usize, y: usize) -> i32 {
-        let mut count = 0;
+    let (pattern, rule) = rle::from_rle_with_rule(text).map_err(|error| error.to_string())?;
+    Ok(PatternFile {
+        pattern,
+        rule,
+        position: rle::position(text),
+    })
+}
+
+/// Whether there's a display server to open a window on. Only X11 and
+/// Wayland sessions can be checked for; elsewhere a display is assumed.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn display_available() -> bool {
+    env::var_os("DISPLAY").is_some() || env::var_os("WAYLAND_DISPLAY").is_some()
+}
+
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+fn display_available() -> bool {
+    true
+}
+
+/// Opens the main window, or returns `None` if SFML can't. SFML reports that
+/// by panicking, which is caught and kept quiet so the caller can explain.
+fn open_window(width: u32, height: u32, title: &str) -> Option<RenderWindow> {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let window = panic::catch_unwind(|| {
+        RenderWindow::new(
+            (width, height),
+            title,
+            Style::DEFAULT,
+            &ContextSettings::default(),
+        )
+    });
+    panic::set_hook(default_hook);
 
-        let grid_width = self.grid.width;
-        let grid_height = self.grid.height;
+    window.ok().filter(|window| window.is_open())
+}
 
-        // Check top and bottom
-        if y != 0 && self.grid.get(x, y - 1) {
-            count += 1
+fn main() {
+    let mut options = match cli::parse(env::args().skip(1), |name| env::var(name).ok()) {
+        Ok(Command::Run(options)) => *options,
+        Ok(Command::PrintUsage) => {
+            println!("{}", cli::USAGE);
+            return;
+        }
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, cli::USAGE);
+            process::exit(2);
         }
-        if y != grid_height - 1 && self.grid.get(x, y + 1) {
-            count += 1
+    };
+
+    // Two-state tables become a plain rule, so they keep the fast paths.
+    let mut rule_table = None;
+    if let Some(path) = &options.rule_table_path {
+        let loaded = fs::read_to_string(path)
+            .map_err(|error| error.to_string())
+            .and_then(|text| text.parse::<RuleTable>().map_err(|error| error.to_string()));
+        match loaded {
+            Ok(table) => match Rule::try_from(&table) {
+                Ok(rule) => options.rule = Some(rule),
+                Err(_) => rule_table = Some(table),
+            },
+            Err(message) => {
+                eprintln!("Couldn't load rule table {}: {}", path, message);
+                process::exit(2);
+            }
         }
+    }
 
-        // Check right and left
-        if x != grid_width - 1 && self.grid.get(x + 1, y) {
-            count += 1
+    let mut game = new_game(&options);
+    game.set_rule_table(rule_table);
+    if let Some(seed) = options.seed {
+        game.reseed(seed);
+    }
+    if let Some(density) = options.density {
+        game.randomize(density);
+    }
+    if let Some(formula) = &options.formula {
+        game.fill_from_formula(formula);
+    }
+    if let Some(path) = &options.load_path {
+        match read_pattern(path) {
+            Ok(file) => {
+                let (position, placement) = match options.load_offset {
+                    Some(offset) => (Some(offset), PlacementMode::Absolute),
+                    None => (file.position, options.placement),
+                };
+                let (x, y) = game.placement_offset(&file.pattern, position, placement);
+                warn_if_clipped(&game, path, &file.pattern, x, y);
+                game.load_pattern(&file.pattern, position, placement);
+                if let Some(note) = file.rule.and_then(|rule| game.offer_rule(rule)) {
+                    println!("{}: {}", path, note);
+                }
+            }
+            Err(message) => {
+                eprintln!("Couldn't load pattern {}: {}", path, message);
+                process::exit(2);
+            }
+        }
+    }
+    if let Some(pattern) = options.library_pattern {
+        game.load_library_pattern(pattern, options.placement);
+    }
+    if !options.stamps.is_empty() {
+        let mut patterns = Vec::new();
+        for (path, _) in &options.stamps {
+            match read_pattern(path) {
+                Ok(file) => patterns.push(file.pattern),
+                Err(message) => {
+                    eprintln!("Couldn't load pattern {}: {}", path, message);
+                    process::exit(2);
+                }
+            }
         }
-        if x != 0 && self.grid.get(x - 1, y) {
-            count += 1
+        let stamps: Vec<_> = patterns
+            .iter()
+            .zip(&options.stamps)
+            .map(|(pattern, &(_, (x, y)))| (pattern, x, y))
+            .collect();
+        for (&(pattern, x, y), (path, _)) in stamps.iter().zip(&options.stamps) {
+            warn_if_clipped(&game, path, pattern, x, y);
         }
 
-        // Check top left and top right
-        if x != 0 && y != 0 && self.grid.get(x - 1, y - 1) {
-            count += 1
+        let conflicts = game.stamp_patterns(&stamps);
+        if !conflicts.is_empty() {
+            let listed: Vec<String> = conflicts
+                .iter()
+                .take(MAX_LISTED_CONFLICTS)
+                .map(|cell| format!("{},{}", cell.x, cell.y))
+                .collect();
+            let more = conflicts.len().saturating_sub(MAX_LISTED_CONFLICTS);
+            eprintln!(
+                "Stamped patterns overlap at {} cells: {}{}",
+                conflicts.len(),
+                listed.join(" "),
+                if more > 0 {
+                    format!(" and {} more", more)
+                } else {
+                    String::new()
+                }
+            );
         }
-        if x != grid_width - 1 && y != 0 && self.grid.get(x + 1, y - 1) {
-            count += 1
+    }
+    for (op, path) in &options.combine {
+        match read_pattern(path) {
+            Ok(file) => game.combine_pattern(&file.pattern, file.position, options.placement, *op),
+            Err(message) => {
+                eprintln!("Couldn't load pattern {}: {}", path, message);
+                process::exit(2);
+            }
         }
-
-        // Check bottom right and bottom left
-        if x != grid_width - 1 && y != grid_height - 1 && self.grid.get(x + 1, y + 1) {
-            count += 1
+    }
+    for &(x, y) in &options.cells {
+        if !game.try_set_cell(x, y, true) {
+            eprintln!("Ignoring cell {},{} from --cells, it's off the board", x, y);
         }
-        if x != 0 && y != grid_height - 1 && self.grid.get(x - 1, y + 1) {
-            count += 1
+    }
+    if let Some(path) = &options.reference_path {
+        let loaded = fs::read_to_string(path)
+            .map_err(|error| error.to_string())
+            .and_then(|text| rle::from_rle(&text).map_err(|error| error.to_string()));
+        match loaded {
+            Ok(reference) => game.set_reference(&reference),
+            Err(message) => {
+                eprintln!("Couldn't load reference {}: {}", path, message);
+                process::exit(2);
+            }
         }
-
-        count
     }
 
-    fn apply_simulation_grid(&mut self) {
-        self.grid.array = self.simulation_grid.array.clone();
+    if let Some((width, height)) = options.resize_to {
+        game.resize(width, height, options.resize_anchor);
     }
 
-    fn process_event(&mut self, event: &Event, window: &RenderWindow) {
-        match event {
-            Event::MouseButtonPressed { x, y, .. } => {
-                let mouse_pos = window.map_pixel_to_coords(Vector2i::new(*x, *y), window.view());
-                let cell_pos = self.get_cell_below_position(mouse_pos);
-                self.toggle_cell(cell_pos);
-            }
-            Event::KeyPressed { code, .. } => {
-                if Key::SPACE == *code {
-                    self.paused = !self.paused;
-                }
-            }
-            _ => (),
-        }
+    if options.bench {
+        bench::run(&mut game, options.bench_duration);
+        return;
     }
 
-    fn update(&mut self) {
-        if self.paused {
-            return;
+    if let Some(path) = &options.gif_path {
+        let recorded = recording::record(
+            &mut game,
+            path,
+            options.gif_generations,
+            options.gif_delay_ms,
+        );
+        if let Err(message) = recorded {
+            eprintln!("Couldn't record {}: {}", path, message);
+            process::exit(1);
         }
+        return;
+    }
 
-        let grid = &self.grid;
-
-        for y in 0..grid.height {
-            for x in 0..grid.width {
-                let is_alive = grid.get(x, y);
-                let neighbors_count = self.get_neighbors_count(x, y);
+    let headless = options.headless || !display_available();
+    if headless && !options.headless {
+        eprintln!("No display found, running headless instead");
+    }
+    if !headless {
+        autosave::offer_restore(&mut game);
+    }
 
-                let mut is_going_to_live = false;
+    // The comparison board starts from the same cells and random state, so
+    // the rule is the only difference between the two.
+    let mut comparison = options.compare_rule.map(|rule| {
+        let mut other = new_game(&options);
+        other.rule = rule;
+        other.grid = game.grid.clone();
+        other.rng = game.rng.clone();
+        other.seed = game.seed;
+        other
+    });
+
+    // Burned in before any callbacks are registered, so the population log
+    // and period warnings start with the first generation the window shows.
+    if !headless {
+        for game in std::iter::once(&mut game).chain(comparison.as_mut()) {
+            headless::burn_in(game, options.burn_in);
+        }
+    }
 
-                if is_alive {
-                    if neighbors_count < 2 {
-                        is_going_to_live = false;
-                    } else if neighbors_count == 2 || neighbors_count == 3 {
-                        is_going_to_live = true;
-                    } else if neighbors_count > 3 {
-                        is_going_to_live = false;
-                    }
-                } else {
-                    if neighbors_count == 3 {
-                        is_going_to_live = true;
-                    }
+    // Shared with the callback so it can be flushed before exiting.
+    let population_log =
+        options
+            .population_log_path
+            .as_ref()
+            .map(|path| match PopulationLog::open(path, &game) {
+                Ok(log) => Rc::new(RefCell::new(log)),
+                Err(error) => {
+                    eprintln!("Couldn't open population log {}: {}", path, error);
+                    process::exit(2);
                 }
+            });
+    if let Some(log) = &population_log {
+        let log = Rc::clone(log);
+        game.on_generation(Box::new(move |game| log.borrow_mut().record(game)));
+    }
 
-                self.simulation_grid.set(x, y, is_going_to_live);
-            }
+    if headless {
+        let met = headless::run(
+            &mut game,
+            &options.stop_conditions,
+            options.max_generations,
+            options.max_period.unwrap_or(cli::DEFAULT_MAX_PERIOD),
+        );
+        if let Some(log) = &population_log {
+            log.borrow_mut().flush();
         }
-
-        self.apply_simulation_grid();
+        process::exit(if met { 0 } else { 1 });
     }
 
-    fn draw(&self, target: &mut impl RenderTarget) {
-        let grid = &self.grid;
+    let mut autosave = Autosave::new(Duration::from_secs(options.autosave_interval));
+
+    // Warns once each time the board falls into a cycle, so a run that has
+    // stalled in a long oscillation isn't mistaken for one still evolving.
+    if let Some(max_period) = options.max_period {
+        let mut detector = PeriodDetector::new(max_period);
+        let mut cycling = false;
+        game.on_generation(Box::new(move |game| {
+            let period = detector.observe(&game.grid);
+            if let (Some(period), false) = (period, cycling) {
+                eprintln!(
+                    "Generation {}: the board repeats with period {}",
+                    game.generation, period
+                );
+            }
+            cycling = period.is_some();
+        }));
+    }
 
-        let mut cell_shape = RectangleShape::new();
+    let font = overlay::load_font(options.font_path.as_deref());
+    if font.is_none() {
+        eprintln!(
+            "No font found, so text overlays are off and help will be printed to stdout \
+             instead; pass --font PATH to use one"
+        );
+    }
 
-        cell_shape.set_size(self.cell_size);
-        cell_shape.set_fill_color(self.cell_color);
+    for game in std::iter::once(&mut game).chain(comparison.as_mut()) {
+        game.text_overlays = font.is_some();
+        game.count_escaped_gliders = true;
 
-        for y in 0..grid.height {
-            for x in 0..grid.width {
-                if grid.get(x, y) {
-                    cell_shape
-                        .set_position((x as f32 * self.cell_size.x, y as f32 * self.cell_size.y));
-                    target.draw(&cell_shape);
-                }
-            }
+        game.paused = options.start_paused;
+        game.sandbox = options.sandbox;
+        game.max_generation = options.max_generation;
+        match options.pause_at {
+            Some(0) => game.paused = true,
+            pause_at => game.remaining_ticks = pause_at,
         }
     }
-}
 
-fn main() {
-    let mut game = Game::new(40, 40);
-
-    let mut window = RenderWindow::new(
-        (400, 400),
-        "Conway's Game of Life",
-        Style::DEFAULT,
-        &ContextSettings::default(),
-    );
-
-    window.set_framerate_limit(30);
-    window.set_view(&View::new(
-        Vector2f::new(200.0, 200.0),
-        Vector2f::new(400.0, 400.0),
-    ));
-
-    let mut elapsed = Time::ZERO;
+    let mut title = game.title();
+    let window_width = if comparison.is_some() { 800 } else { 400 };
+    let Some(mut window) = open_window(window_width, 400, &title) else {
+        eprintln!("Couldn't open a window. Pass --headless to run without one.");
+        process::exit(1);
+    };
+
+    window.set_framerate_limit(FRAME_RATE);
+    let mut frame_rate = FRAME_RATE;
+    let mut world_size = game.world_size();
+    let view_size = options.view_size.unwrap_or(world_size);
+    let view_center = options.view_center.unwrap_or(world_size / 2.0);
+    let mut view = View::new(view_center, view_size);
+    if options.letterbox {
+        let size = window.size();
+        letterbox(&mut view, size.x, size.y, comparison.is_some());
+    }
+    window.set_view(&view);
+
     let mut clock = Clock::start();
 
-    let tick_duration = Time::seconds(0.2);
-    let mut elapsed_since_last_tick = Time::ZERO;
+    let mut scheduler = TickScheduler::new(FRAME_TICK_BUDGET);
+    let mut running_behind = false;
+    // Only the change from some live cells in view to none pauses.
+    let mut view_had_life = game.view_has_live_cells(&view);
+    // Drawing is timed one frame behind, since the breakdown is part of the
+    // frame being drawn.
+    let mut draw_time = Time::ZERO;
+
+    let mut capture =
+        options
+            .framebuffer_path
+            .as_ref()
+            .map(|path| match Capture::open(path, &game) {
+                Ok(capture) => {
+                    eprintln!("{}", capture.describe());
+                    capture
+                }
+                Err(message) => {
+                    eprintln!("Couldn't capture to {}: {}", path, message);
+                    process::exit(1);
+                }
+            });
+
+    let mut equilibrium = options.equilibrium_window.map(|window| {
+        EquilibriumAlert::new(
+            window,
+            options.equilibrium_variance,
+            options.equilibrium_pause,
+        )
+    });
+
+    let mut reveal = (!options.reveal_duration.is_zero()).then(|| {
+        Reveal::start(
+            &mut game,
+            &mut comparison,
+            options.reveal_duration,
+            options.reveal_order,
+        )
+    });
 
     while window.is_open() {
         while let Some(event) = window.poll_event() {
-            game.process_event(&event, &window);
+            // Any key cuts the intro short, and does nothing else.
+            if let (Some(intro), Event::KeyPressed { .. }) = (&mut reveal, &event) {
+                intro.skip(&mut game, &mut comparison);
+                reveal = None;
+                continue;
+            }
+
+            match &mut comparison {
+                Some(other) => compare::dispatch(
+                    &event,
+                    &mut game,
+                    other,
+                    &mut window,
+                    &view,
+                    options.mirror_edits,
+                ),
+                None => game.process_event(&event, &window),
+            }
 
             match event {
-                Event::Closed => window.close(),
+                Event::Closed => {
+                    if let Some(path) = &options.save_on_exit_path {
+                        autosave::save_on_exit(&game, path);
+                    }
+                    window.close();
+                }
+                Event::MouseWheelScrolled {
+                    wheel: Wheel::Vertical,
+                    delta,
+                    x,
+                    y,
+                } => {
+                    let pointer_view = match comparison {
+                        Some(_) => compare::pane_view_at(&view, &window, x),
+                        None => view.to_owned(),
+                    };
+                    let anchor = window.map_pixel_to_coords(Vector2i::new(x, y), &pointer_view);
+                    zoom_view(
+                        &mut view,
+                        anchor,
+                        ZOOM_STEP.powf(-delta),
+                        game.cell_size.x * MIN_VIEW_CELLS,
+                        world_size.x * MAX_VIEW_BOARDS,
+                    );
+                    window.set_view(&view);
+                }
+                Event::Resized { width, height } if options.letterbox => {
+                    letterbox(&mut view, width, height, comparison.is_some());
+                    window.set_view(&view);
+                }
                 _ => (),
             }
         }
 
-        if elapsed_since_last_tick > tick_duration {
-            elapsed_since_last_tick -= tick_duration;
+        if let Some(intro) = &mut reveal {
+            if !intro.tick(&mut game, &mut comparison) {
+                reveal = None;
+            }
+        }
+
+        // Catch up on every tick that's due, but give up once the budget is
+        // spent so a heavy board can't starve input handling and rendering.
+        let frame_clock = Clock::start();
+        let (max_speed, tick_duration) = (game.max_speed, game.tick_duration);
+        let ticks = scheduler.run_frame(
+            options.ticks_per_frame,
+            max_speed,
+            tick_duration,
+            &frame_clock,
+            || step(&mut game, &mut comparison),
+        );
+        let update_time = frame_clock.elapsed_time();
+
+        if let Some(equilibrium) = &mut equilibrium {
+            equilibrium.check(&mut game);
+        }
 
-            game.update();
+        // Frames without a due tick say nothing about whether we've caught up.
+        if ticks.run > 0 && ticks.out_of_budget != running_behind {
+            running_behind = ticks.out_of_budget;
+            if running_behind {
+                eprintln!("Simulation is running behind real time");
+            } else {
+                eprintln!("Simulation caught up with real time");
+            }
         }
 
+        // Growing is even on all sides, so moving the view by half the
+        // growth keeps the cells where they were on screen. After shrinking
+        // the view may look past the board, so it's fitted to it instead.
+        if game.world_size() != world_size {
+            let new_size = game.world_size();
+            if new_size.x < world_size.x || new_size.y < world_size.y {
+                view.set_size(new_size);
+                view.set_center(new_size / 2.0);
+                if options.letterbox {
+                    let size = window.size();
+                    letterbox(&mut view, size.x, size.y, comparison.is_some());
+                }
+            } else {
+                view.move_((new_size - world_size) / 2.0);
+            }
+            window.set_view(&view);
+            world_size = new_size;
+        }
+
+        if game.following {
+            match game.live_centroid() {
+                Some(centroid) => {
+                    let center = view.center();
+                    view.set_center(center + (centroid - center) * FOLLOW_SMOOTHING);
+                    window.set_view(&view);
+                }
+                None => {
+                    game.following = false;
+                    game.show_message("Board is empty, stopped following".to_string());
+                }
+            }
+        }
+
+        if options.pause_when_view_empty {
+            let has_life = game.view_has_live_cells(&view);
+            if view_had_life && !has_life && !game.paused {
+                game.paused = true;
+                game.show_message(format!(
+                    "Paused at generation {}: no live cells left in view",
+                    game.generation
+                ));
+            }
+            view_had_life = has_life;
+        }
+
+        // `--ticks-per-frame` ties speed to frames, so painting mustn't change it.
+        let wanted_frame_rate = if game.max_speed {
+            0
+        } else if game.is_painting() && options.ticks_per_frame.is_none() {
+            PAINTING_FRAME_RATE
+        } else {
+            FRAME_RATE
+        };
+        if wanted_frame_rate != frame_rate {
+            frame_rate = wanted_frame_rate;
+            window.set_framerate_limit(frame_rate);
+        }
+
+        autosave.tick(&game);
+
+        if game.title() != title {
+            title = game.title();
+            window.set_title(&title);
+        }
+
+        let draw_clock = Clock::start();
         window.clear(Color::rgb(0, 0, 0));
-        game.draw(&mut window);
+        match &comparison {
+            Some(other) => compare::draw(&mut window, font.as_deref(), &game, other, &view),
+            None => {
+                game.draw(&mut window);
+                if let Some(font) = &font {
+                    overlay::draw_selection_size(&mut window, font, &game);
+                    overlay::draw_inspector(&mut window, font, &game);
+                    if game.show_cell_labels {
+                        overlay::draw_cell_labels(&mut window, font, &game);
+                    }
+                    if game.show_rulers {
+                        overlay::draw_rulers(&mut window, font, &game);
+                    }
+                }
+            }
+        }
+        if game.show_population_graph {
+            overlay::draw_population_graph(&mut window, &game);
+        }
+        if let Some(font) = &font {
+            overlay::draw_message(&mut window, font, &game);
+            if game.show_status {
+                overlay::draw_status(&mut window, font, &game);
+            }
+            overlay::draw_oscillator_phase(&mut window, font, &game);
+            // When comparing, the editor sits over the right-hand board,
+            // which is the one its clicks reach.
+            let edited = comparison.as_ref().unwrap_or(&game);
+            if edited.show_rule_editor {
+                overlay::draw_rule_editor(&mut window, font, edited);
+            }
+            if game.show_timings {
+                let phases = [
+                    ("update", update_time),
+                    ("neighbors", ticks.step_time),
+                    ("draw", draw_time),
+                ];
+                overlay::draw_timings(&mut window, font, &phases);
+            }
+            if game.show_help {
+                game.help_pages = overlay::draw_help(&mut window, font, &game);
+            }
+        }
+        // Measured before `display`, which sleeps to hold the frame rate.
+        draw_time = draw_clock.elapsed_time();
         window.display();
 
-        elapsed = clock.restart();
-        elapsed_since_last_tick += elapsed;
+        if let Some(capture) = &mut capture {
+            capture.frame(&game);
+        }
+
+        scheduler.add_elapsed(clock.restart());
+    }
+
+    if let Some(log) = &population_log {
+        log.borrow_mut().flush();
     }
 }
@@ -0,0 +1,556 @@
+use std::time::Duration;
+
+use sfml::{
+    graphics::{
+        Color, FloatRect, Font, PrimitiveType, RectangleShape, RenderTarget, Shape, Text,
+        Transformable, Vertex, VertexArray,
+    },
+    system::{Time, Vector2f, Vector2i, Vector2u},
+    SfBox,
+};
+
+use crate::{
+    bindings,
+    game::{Game, UpdateMode, POPULATION_HISTORY_LENGTH},
+    grid::{Boundary, GridTopology},
+};
+
+const FONT_SEARCH_PATHS: &[&str] = &[
+    "/usr/share/fonts/truetype/dejavu/DejaVuSansMono.ttf",
+    "/usr/share/fonts/TTF/DejaVuSansMono.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSansMono.ttf",
+    "/usr/share/fonts/truetype/liberation/LiberationMono-Regular.ttf",
+    "/usr/share/fonts/liberation-mono/LiberationMono-Regular.ttf",
+    "/usr/share/fonts/truetype/noto/NotoSansMono-Regular.ttf",
+    "/usr/share/fonts/noto/NotoSansMono-Regular.ttf",
+    "/System/Library/Fonts/Menlo.ttc",
+    "/Library/Fonts/Arial.ttf",
+    "C:\\Windows\\Fonts\\consola.ttf",
+    "C:\\Windows\\Fonts\\arial.ttf",
+];
+
+const CHARACTER_SIZE: u32 = 14;
+/// How long a message posted with `Game::show_message` stays up.
+const MESSAGE_DURATION: Duration = Duration::from_secs(3);
+const PANEL_PADDING: f32 = 12.0;
+const LABEL_PADDING: f32 = 3.0;
+const GRAPH_SIZE: Vector2f = Vector2f::new(200.0, 80.0);
+/// Thickness of the rulers along the window's top and left edges.
+const RULER_SIZE: f32 = 20.0;
+/// The closest two ruler labels may get, in pixels.
+const RULER_MIN_SPACING: f32 = 50.0;
+/// How many pixels wide and tall cells must be drawn before each gets a
+/// coordinate label.
+const CELL_LABEL_MIN_SIZE: f32 = 40.0;
+/// Side of each rule editor checkbox, and the gap between two.
+const RULE_BOX_SIZE: f32 = 18.0;
+const RULE_BOX_GAP: f32 = 4.0;
+/// Room left of the checkboxes for the row names, and above for the counts.
+const RULE_LABEL_WIDTH: f32 = 70.0;
+const RULE_HEADER_HEIGHT: f32 = 18.0;
+/// Where the rule editor's top edge sits, below the oscillator phase label.
+const RULE_EDITOR_TOP: f32 = 40.0;
+
+/// Loads the font every text overlay shares: `custom` when given and
+/// readable, otherwise the first one found in the usual system places.
+pub fn load_font(custom: Option<&str>) -> Option<SfBox<Font>> {
+    if let Some(path) = custom {
+        match Font::from_file(path) {
+            Some(font) => return Some(font),
+            None => eprintln!(
+                "Couldn't load the font {}, looking for a system font instead",
+                path
+            ),
+        }
+    }
+
+    FONT_SEARCH_PATHS
+        .iter()
+        .find_map(|path| Font::from_file(path))
+}
+
+/// Draws `lines` on a semi-transparent panel in screen space, regardless of
+/// the view the target currently uses.
+fn draw_panel(
+    target: &mut impl RenderTarget,
+    font: &Font,
+    position: Vector2f,
+    lines: &[String],
+    padding: f32,
+) {
+    let world_view = target.view().to_owned();
+    let screen_view = target.default_view().to_owned();
+    target.set_view(&screen_view);
+
+    let mut text = Text::new(&lines.join("\n"), font, CHARACTER_SIZE);
+    text.set_fill_color(Color::rgb(255, 255, 255));
+    text.set_position((position.x + padding, position.y + padding));
+
+    let bounds = text.local_bounds();
+    let mut panel = RectangleShape::new();
+    panel.set_size((
+        bounds.left + bounds.width + padding * 2.0,
+        bounds.top + bounds.height + padding * 2.0,
+    ));
+    panel.set_position(position);
+    panel.set_fill_color(Color::rgba(0, 0, 0, 200));
+    panel.set_outline_color(Color::rgb(128, 128, 128));
+    panel.set_outline_thickness(1.0);
+
+    target.draw(&panel);
+    target.draw(&text);
+
+    target.set_view(&world_view);
+}
+
+/// Page `game.help_page` of the controls, headed by the random seed so a
+/// session's random actions can be reported and replayed. Each page holds
+/// as many lines as fit the window's height. Returns how many pages there
+/// are.
+pub fn draw_help(target: &mut impl RenderTarget, font: &Font, game: &Game) -> usize {
+    const MARGIN: f32 = 20.0;
+    const HEADER_LINES: usize = 2;
+
+    let entries = bindings::help_lines();
+    let room = target.size().y as f32 - 2.0 * (MARGIN + PANEL_PADDING);
+    let fitting = (room / font.line_spacing(CHARACTER_SIZE)) as usize;
+    let per_page = fitting.saturating_sub(HEADER_LINES).max(1);
+    let pages = entries.len().div_ceil(per_page).max(1);
+    let page = game.help_page.min(pages - 1);
+
+    let mut header = format!("Controls (seed {})", game.seed);
+    if pages > 1 {
+        header.push_str(&format!(", page {} of {}, H for more", page + 1, pages));
+    }
+    let mut lines = vec![header, String::new()];
+    lines.extend(entries.into_iter().skip(page * per_page).take(per_page));
+
+    draw_panel(
+        target,
+        font,
+        Vector2f::new(MARGIN, MARGIN),
+        &lines,
+        PANEL_PADDING,
+    );
+    pages
+}
+
+/// A one-line label at `position` in window pixels.
+pub fn draw_caption(target: &mut impl RenderTarget, font: &Font, text: &str, position: Vector2f) {
+    draw_panel(target, font, position, &[text.to_string()], LABEL_PADDING);
+}
+
+/// The active rule, boundary and speed, plus the update mode when it isn't
+/// the usual one, over a line of population counts, in the bottom-right
+/// corner.
+pub fn draw_status(target: &mut impl RenderTarget, font: &Font, game: &Game) {
+    let speed = if game.max_speed {
+        "max speed".to_string()
+    } else {
+        format!("{:.1} gen/s", 1.0 / game.tick_duration.as_seconds())
+    };
+    let mut line = format!("{} | {} | {}", game.rule.describe(), game.boundary, speed);
+    if game.topology != GridTopology::Square {
+        line.push_str(&format!(" | {} cells", game.topology));
+    }
+    if game.update_mode != UpdateMode::Synchronous {
+        line.push_str(&format!(" | {}", game.update_mode));
+    }
+    let mut counts = format!(
+        "Generation {} | {} alive | {} births, {} deaths since cleared",
+        game.generation,
+        game.grid.population(),
+        game.total_births,
+        game.total_deaths
+    );
+    // Gliders only leave the board for good past a dead boundary, and only
+    // square cells have them.
+    if game.count_escaped_gliders
+        && game.boundary == Boundary::Dead
+        && game.topology == GridTopology::Square
+    {
+        counts.push_str(&format!(" | {} gliders escaped", game.escaped_gliders));
+    }
+    let lines = [line, counts];
+
+    let text = Text::new(&lines.join("\n"), font, CHARACTER_SIZE);
+    let bounds = text.local_bounds();
+    let size = target.size();
+    let position = Vector2f::new(
+        size.x as f32 - bounds.left - bounds.width - 2.0 * LABEL_PADDING - 10.0,
+        size.y as f32 - bounds.top - bounds.height - 2.0 * LABEL_PADDING - 10.0,
+    );
+
+    draw_panel(target, font, position, &lines, LABEL_PADDING);
+}
+
+/// A line graph of `Game::population_history` in the top-right corner,
+/// scaled so the highest population in it touches the top.
+pub fn draw_population_graph(target: &mut impl RenderTarget, game: &Game) {
+    let history = &game.population_history;
+    let world_view = target.view().to_owned();
+    let screen_view = target.default_view().to_owned();
+    target.set_view(&screen_view);
+
+    let origin = Vector2f::new(target.size().x as f32 - GRAPH_SIZE.x - 10.0, 10.0);
+    let mut panel = RectangleShape::with_size(GRAPH_SIZE);
+    panel.set_position(origin);
+    panel.set_fill_color(Color::rgba(0, 0, 0, 200));
+    panel.set_outline_color(Color::rgb(128, 128, 128));
+    panel.set_outline_thickness(1.0);
+    target.draw(&panel);
+
+    let highest = history.iter().copied().max().unwrap_or(0).max(1) as f32;
+    // Leaves room for one point per generation the history can hold, so the
+    // line fills in from the left and then scrolls.
+    let step = GRAPH_SIZE.x / (POPULATION_HISTORY_LENGTH - 1) as f32;
+    let mut line = VertexArray::new(PrimitiveType::LINE_STRIP, 0);
+    for (i, &population) in history.iter().enumerate() {
+        let position = Vector2f::new(
+            origin.x + i as f32 * step,
+            origin.y + GRAPH_SIZE.y * (1.0 - population as f32 / highest),
+        );
+        line.append(&Vertex::with_pos_color(position, Color::rgb(120, 220, 120)));
+    }
+    target.draw(&line);
+
+    target.set_view(&world_view);
+}
+
+/// How many pixels wide and tall a cell is drawn under the current view.
+fn cell_pixels(target: &impl RenderTarget, game: &Game) -> Vector2f {
+    let size = target.size();
+    let view = target.view();
+    let (view_size, viewport) = (view.size(), view.viewport());
+    Vector2f::new(
+        game.cell_size.x * size.x as f32 * viewport.width / view_size.x,
+        game.cell_size.y * size.y as f32 * viewport.height / view_size.y,
+    )
+}
+
+/// Writes each visible cell's coordinates inside it, but only once cells
+/// are drawn at least `CELL_LABEL_MIN_SIZE` pixels across, so zoomed out
+/// boards don't turn into a blur of text. Only cells the view shows are
+/// labelled, so the cost follows the window size rather than the board's.
+pub fn draw_cell_labels(target: &mut impl RenderTarget, font: &Font, game: &Game) {
+    let pixels = cell_pixels(target, game);
+    if pixels.x < CELL_LABEL_MIN_SIZE || pixels.y < CELL_LABEL_MIN_SIZE {
+        return;
+    }
+    let world_view = target.view().to_owned();
+    let Some(visible) = game.visible_cells(&world_view) else {
+        return;
+    };
+    let (first, last) = visible.corners();
+
+    let labels: Vec<(String, Vector2i)> = (first.y..=last.y)
+        .flat_map(|y| (first.x..=last.x).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            let corner = target.map_coords_to_pixel_current_view(game.cell_position(x, y));
+            (format!("{},{}", x, y), corner)
+        })
+        .collect();
+
+    let screen_view = target.default_view().to_owned();
+    target.set_view(&screen_view);
+
+    // An outline keeps the text readable on live and dead cells alike.
+    let mut text = Text::new("", font, CHARACTER_SIZE - 4);
+    text.set_fill_color(Color::rgb(255, 255, 255));
+    text.set_outline_color(Color::rgb(0, 0, 0));
+    text.set_outline_thickness(1.0);
+    for (label, corner) in &labels {
+        text.set_string(label);
+        text.set_position((corner.x as f32 + 2.0, corner.y as f32 + 2.0));
+        target.draw(&text);
+    }
+
+    target.set_view(&world_view);
+}
+
+/// The smallest of 1, 2, 5, 10, 20, 50 and so on cells that keeps ruler
+/// labels at least `RULER_MIN_SPACING` apart when a cell is `cell_pixels`
+/// wide.
+fn ruler_interval(cell_pixels: f32) -> usize {
+    (0..10)
+        .flat_map(|power| [1, 2, 5].map(|multiple| multiple * 10usize.pow(power)))
+        .find(|&interval| interval as f32 * cell_pixels >= RULER_MIN_SPACING)
+        .unwrap_or(10usize.pow(10))
+}
+
+/// Cell indices along the top and left edges of the window, at an interval
+/// that suits the zoom. Positions come from the current view, so the labels
+/// stay on their cells as it pans and zooms.
+pub fn draw_rulers(target: &mut impl RenderTarget, font: &Font, game: &Game) {
+    let world_view = target.view().to_owned();
+    let Some(visible) = game.visible_cells(&world_view) else {
+        return;
+    };
+    let (first, last) = visible.corners();
+
+    // Pixel offsets of each labelled column and row, worked out while the
+    // world view is still in place.
+    let size = target.size();
+    let cell_pixels = cell_pixels(target, game);
+    let pixel = |world: Vector2f| target.map_coords_to_pixel_current_view(world);
+    let columns: Vec<(usize, f32)> = {
+        let interval = ruler_interval(cell_pixels.x);
+        (first.x.next_multiple_of(interval)..=last.x)
+            .step_by(interval)
+            .map(|x| (x, pixel(game.cell_position(x, 0)).x as f32))
+            .collect()
+    };
+    let rows: Vec<(usize, f32)> = {
+        let interval = ruler_interval(cell_pixels.y);
+        (first.y.next_multiple_of(interval)..=last.y)
+            .step_by(interval)
+            .map(|y| (y, pixel(game.cell_position(0, y)).y as f32))
+            .collect()
+    };
+
+    let screen_view = target.default_view().to_owned();
+    target.set_view(&screen_view);
+
+    let mut strip = RectangleShape::new();
+    strip.set_fill_color(Color::rgba(0, 0, 0, 200));
+    for strip_size in [
+        Vector2f::new(size.x as f32, RULER_SIZE),
+        Vector2f::new(RULER_SIZE, size.y as f32),
+    ] {
+        strip.set_size(strip_size);
+        target.draw(&strip);
+    }
+
+    let tick_color = Color::rgb(160, 160, 160);
+    let mut ticks = VertexArray::new(PrimitiveType::LINES, 0);
+    let mut label = Text::new("", font, CHARACTER_SIZE - 4);
+    label.set_fill_color(Color::rgb(255, 255, 255));
+
+    for &(x, position) in &columns {
+        if position < RULER_SIZE {
+            continue;
+        }
+        ticks.append(&Vertex::with_pos_color(
+            Vector2f::new(position, 0.0),
+            tick_color,
+        ));
+        ticks.append(&Vertex::with_pos_color(
+            Vector2f::new(position, RULER_SIZE),
+            tick_color,
+        ));
+        label.set_string(&x.to_string());
+        label.set_position((position + 2.0, 2.0));
+        target.draw(&label);
+    }
+    for &(y, position) in &rows {
+        if position < RULER_SIZE {
+            continue;
+        }
+        ticks.append(&Vertex::with_pos_color(
+            Vector2f::new(0.0, position),
+            tick_color,
+        ));
+        ticks.append(&Vertex::with_pos_color(
+            Vector2f::new(RULER_SIZE, position),
+            tick_color,
+        ));
+        label.set_string(&y.to_string());
+        label.set_position((2.0, position + 2.0));
+        target.draw(&label);
+    }
+    target.draw(&ticks);
+
+    target.set_view(&world_view);
+}
+
+/// A breakdown of where the last frame's time went, one `phase: 1.2ms` line
+/// per phase, in the top-left corner.
+pub fn draw_timings(target: &mut impl RenderTarget, font: &Font, phases: &[(&str, Time)]) {
+    let lines: Vec<String> = phases
+        .iter()
+        .map(|(phase, time)| format!("{}: {:.1}ms", phase, time.as_microseconds() as f32 / 1000.0))
+        .collect();
+
+    draw_panel(
+        target,
+        font,
+        Vector2f::new(10.0, 10.0),
+        &lines,
+        LABEL_PADDING,
+    );
+}
+
+/// Labels the selection with its size in cells, just past its bottom-right
+/// corner, so it follows the drag as it grows.
+pub fn draw_selection_size(target: &mut impl RenderTarget, font: &Font, game: &Game) {
+    let (selection, bounds) = match (game.selection, game.selection_world_bounds()) {
+        (Some(selection), Some(bounds)) => (selection, bounds),
+        _ => return,
+    };
+
+    let corner = Vector2f::new(bounds.left + bounds.width, bounds.top + bounds.height);
+    let Vector2i { x, y } = target.map_coords_to_pixel_current_view(corner);
+    let size = selection.size();
+
+    draw_panel(
+        target,
+        font,
+        Vector2f::new(x as f32 + 4.0, y as f32 + 4.0),
+        &[format!("{} × {}", size.x, size.y)],
+        LABEL_PADDING,
+    );
+}
+
+/// Shows the game's latest message along the bottom edge until it expires.
+pub fn draw_message(target: &mut impl RenderTarget, font: &Font, game: &Game) {
+    let text = match &game.message {
+        Some((text, posted)) if posted.elapsed() < MESSAGE_DURATION => text,
+        _ => return,
+    };
+
+    let height = target.size().y as f32;
+    draw_panel(
+        target,
+        font,
+        Vector2f::new(10.0, height - CHARACTER_SIZE as f32 - 30.0),
+        std::slice::from_ref(text),
+        PANEL_PADDING / 2.0,
+    );
+}
+
+/// The placed library oscillator's phase in the top-right corner, for
+/// following it through its cycle a step at a time.
+pub fn draw_oscillator_phase(target: &mut impl RenderTarget, font: &Font, game: &Game) {
+    let Some(label) = game.oscillator_phase() else {
+        return;
+    };
+
+    let text = Text::new(&label, font, CHARACTER_SIZE);
+    let width = text.local_bounds().width + LABEL_PADDING * 2.0;
+    draw_caption(
+        target,
+        font,
+        &label,
+        Vector2f::new(target.size().x as f32 - width - 10.0, 10.0),
+    );
+}
+
+/// The rule editor's top-left corner in a window of `window_size` pixels,
+/// keeping it against the right edge.
+fn rule_editor_origin(window_size: Vector2u) -> Vector2f {
+    let width = PANEL_PADDING * 2.0 + RULE_LABEL_WIDTH + 9.0 * (RULE_BOX_SIZE + RULE_BOX_GAP)
+        - RULE_BOX_GAP;
+    Vector2f::new(window_size.x as f32 - width - 10.0, RULE_EDITOR_TOP)
+}
+
+/// The checkbox for neighbor count `count` in the birth row or, with
+/// `survival`, the survival row, in window pixels.
+fn rule_box(origin: Vector2f, survival: bool, count: usize) -> FloatRect {
+    let row = if survival { 1.0 } else { 0.0 };
+    FloatRect::new(
+        origin.x + PANEL_PADDING + RULE_LABEL_WIDTH + count as f32 * (RULE_BOX_SIZE + RULE_BOX_GAP),
+        origin.y + PANEL_PADDING + RULE_HEADER_HEIGHT + row * (RULE_BOX_SIZE + RULE_BOX_GAP),
+        RULE_BOX_SIZE,
+        RULE_BOX_SIZE,
+    )
+}
+
+/// The rule editor checkbox under window pixel `(x, y)`, as whether it's in
+/// the survival row and its neighbor count.
+pub fn rule_editor_hit(window_size: Vector2u, x: i32, y: i32) -> Option<(bool, usize)> {
+    let origin = rule_editor_origin(window_size);
+    let point = Vector2f::new(x as f32, y as f32);
+    [false, true]
+        .into_iter()
+        .flat_map(|survival| (0..9).map(move |count| (survival, count)))
+        .find(|&(survival, count)| rule_box(origin, survival, count).contains(point))
+}
+
+/// A panel of birth and survival checkboxes for neighbor counts 0 to 8,
+/// ticked where `game.rule` has them. Clicking one flips it; see
+/// `rule_editor_hit`.
+pub fn draw_rule_editor(target: &mut impl RenderTarget, font: &Font, game: &Game) {
+    let world_view = target.view().to_owned();
+    let screen_view = target.default_view().to_owned();
+    target.set_view(&screen_view);
+
+    let origin = rule_editor_origin(target.size());
+    let last = rule_box(origin, true, 8);
+    let mut panel = RectangleShape::new();
+    panel.set_position(origin);
+    panel.set_size((
+        last.left + last.width + PANEL_PADDING - origin.x,
+        last.top + last.height + PANEL_PADDING - origin.y,
+    ));
+    panel.set_fill_color(Color::rgba(0, 0, 0, 200));
+    panel.set_outline_color(Color::rgb(128, 128, 128));
+    panel.set_outline_thickness(1.0);
+    target.draw(&panel);
+
+    let mut label = Text::new("", font, CHARACTER_SIZE);
+    label.set_fill_color(Color::rgb(255, 255, 255));
+    for count in 0..9 {
+        let column = rule_box(origin, false, count);
+        label.set_string(&count.to_string());
+        label.set_position((column.left + 4.0, column.top - RULE_HEADER_HEIGHT));
+        target.draw(&label);
+    }
+
+    let mut checkbox = RectangleShape::new();
+    checkbox.set_size((RULE_BOX_SIZE, RULE_BOX_SIZE));
+    checkbox.set_outline_color(Color::rgb(200, 200, 200));
+    checkbox.set_outline_thickness(-1.0);
+    for (survival, name, counts) in [
+        (false, "Birth", &game.rule.birth),
+        (true, "Survival", &game.rule.survival),
+    ] {
+        let first = rule_box(origin, survival, 0);
+        label.set_string(name);
+        label.set_position((origin.x + PANEL_PADDING, first.top));
+        target.draw(&label);
+
+        for (count, &set) in counts.iter().enumerate() {
+            let bounds = rule_box(origin, survival, count);
+            checkbox.set_position((bounds.left, bounds.top));
+            checkbox.set_fill_color(if set {
+                Color::rgb(120, 255, 140)
+            } else {
+                Color::TRANSPARENT
+            });
+            target.draw(&checkbox);
+        }
+    }
+
+    target.set_view(&world_view);
+}
+
+/// A tooltip by the hovered cell with its state, live neighbors and what it
+/// becomes next generation.
+pub fn draw_inspector(target: &mut impl RenderTarget, font: &Font, game: &Game) {
+    let cell = match (game.show_inspector, game.hovered_cell) {
+        (true, Some(cell)) => cell,
+        _ => return,
+    };
+
+    let state = |alive| if alive { "alive" } else { "dead" };
+    let lines = [
+        format!("Cell {},{}", cell.x, cell.y),
+        format!("Now: {}", state(game.grid.get(cell.x, cell.y))),
+        format!("Neighbors: {}", game.cached_neighbor_count(cell.x, cell.y)),
+        format!(
+            "Next: {}",
+            state(game.cached_next_cell_state(cell.x, cell.y))
+        ),
+    ];
+
+    let corner = game.cell_position(cell.x, cell.y) + game.cell_size;
+    let Vector2i { x, y } = target.map_coords_to_pixel_current_view(corner);
+
+    draw_panel(
+        target,
+        font,
+        Vector2f::new(x as f32 + 8.0, y as f32 + 8.0),
+        &lines,
+        LABEL_PADDING,
+    );
+}
@@ -0,0 +1,206 @@
+use crate::grid::BoolGrid2D;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A board stored one bit per cell, 64 cells to a word. Bit `k` of word `i`
+/// in a row is the cell at `x = i * 64 + k`.
+///
+/// Packing lets `step` work out 64 cells at once with bitwise adders instead
+/// of counting each cell's neighbors. It only knows Conway's rule on a board
+/// with dead edges, so `Game` falls back to the per-cell loop for anything
+/// else.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PackedGrid {
+    words: Vec<u64>,
+    words_per_row: usize,
+    width: usize,
+    height: usize,
+}
+
+/// Adds three one-bit numbers in every bit position, returning the sum and
+/// carry words.
+fn full_add(a: u64, b: u64, c: u64) -> (u64, u64) {
+    let partial = a ^ b;
+    (partial ^ c, (a & b) | (partial & c))
+}
+
+fn half_add(a: u64, b: u64) -> (u64, u64) {
+    (a ^ b, a & b)
+}
+
+impl PackedGrid {
+    pub fn new(width: usize, height: usize) -> PackedGrid {
+        let words_per_row = width.div_ceil(WORD_BITS);
+        PackedGrid {
+            words: vec![0; words_per_row * height],
+            words_per_row,
+            width,
+            height,
+        }
+    }
+
+    pub fn from_grid(grid: &BoolGrid2D) -> PackedGrid {
+        let mut packed = PackedGrid::new(grid.width, grid.height);
+        for (x, y) in grid.live_cells() {
+            packed.words[y * packed.words_per_row + x / WORD_BITS] |= 1 << (x % WORD_BITS);
+        }
+        packed
+    }
+
+    /// Writes the cells back into `grid`, which must be the same size.
+    pub fn write_to(&self, grid: &mut BoolGrid2D) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                grid.set(x, y, self.get(x, y));
+            }
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.words[y * self.words_per_row + x / WORD_BITS] >> (x % WORD_BITS) & 1 == 1
+    }
+
+    /// Word `i` of row `y`, with rows past either edge reading as dead.
+    fn word(&self, y: isize, i: usize) -> u64 {
+        if y < 0 || y as usize >= self.height {
+            return 0;
+        }
+        self.words[y as usize * self.words_per_row + i]
+    }
+
+    /// Each cell's west and east neighbors in row `y`, lined up with word `i`.
+    fn west_and_east(&self, y: isize, i: usize) -> (u64, u64) {
+        let word = self.word(y, i);
+        let before = if i > 0 { self.word(y, i - 1) } else { 0 };
+        let after = if i + 1 < self.words_per_row {
+            self.word(y, i + 1)
+        } else {
+            0
+        };
+
+        (
+            word << 1 | before >> (WORD_BITS - 1),
+            word >> 1 | after << (WORD_BITS - 1),
+        )
+    }
+
+    /// Whether row `y` holds a live cell, with rows past either edge empty.
+    fn row_occupied(occupied: &[bool], y: isize) -> bool {
+        usize::try_from(y)
+            .ok()
+            .and_then(|y| occupied.get(y))
+            .copied()
+            .unwrap_or(false)
+    }
+
+    /// The next generation under B3/S23.
+    ///
+    /// The eight neighbor words of each word are summed bit-wise through a
+    /// tree of adders into `ones` and `twos` digits plus a `fours` flag for
+    /// counts of four or more. A cell lives on a count of exactly three, or
+    /// of exactly two if it's already alive.
+    ///
+    /// Rows that are empty along with both rows next to them are skipped,
+    /// since every cell there has no live neighbors and stays dead. On a
+    /// sparse board that's most of them.
+    pub fn step(&self) -> PackedGrid {
+        let mut next = PackedGrid::new(self.width, self.height);
+        let last_word_mask = match self.width % WORD_BITS {
+            0 => u64::MAX,
+            bits => (1 << bits) - 1,
+        };
+        let occupied: Vec<bool> = self
+            .words
+            .chunks(self.words_per_row)
+            .map(|row| row.iter().any(|&word| word != 0))
+            .collect();
+
+        for y in 0..self.height as isize {
+            if (-1..=1).all(|dy| !PackedGrid::row_occupied(&occupied, y + dy)) {
+                continue;
+            }
+
+            for i in 0..self.words_per_row {
+                let (north_west, north_east) = self.west_and_east(y - 1, i);
+                let (west, east) = self.west_and_east(y, i);
+                let (south_west, south_east) = self.west_and_east(y + 1, i);
+                let north = self.word(y - 1, i);
+                let south = self.word(y + 1, i);
+                let alive = self.word(y, i);
+
+                let (sum_a, carry_a) = full_add(north_west, north, north_east);
+                let (sum_b, carry_b) = full_add(west, east, south_west);
+                let (sum_c, carry_c) = half_add(south, south_east);
+                let (ones, carry_d) = full_add(sum_a, sum_b, sum_c);
+                let (partial_twos, carry_e) = full_add(carry_a, carry_b, carry_c);
+                let (twos, carry_f) = half_add(partial_twos, carry_d);
+                let fours = carry_e | carry_f;
+
+                let mut word = twos & !fours & (ones | alive);
+                if i + 1 == self.words_per_row {
+                    word &= last_word_mask;
+                }
+                next.words[y as usize * self.words_per_row + i] = word;
+            }
+        }
+
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{grid::Boundary, rng::Rng, rule::Rule};
+
+    /// B3/S23 on dead edges, counting every cell's neighbors one by one.
+    fn naive_step(grid: &BoolGrid2D) -> BoolGrid2D {
+        let (width, height) = (grid.width, grid.height);
+        let mut next = BoolGrid2D::new(width, height);
+        for (x, y, alive) in grid.cells() {
+            let neighbors = (-1..=1)
+                .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+                .filter(|&offset| offset != (0, 0))
+                .filter_map(|(dx, dy)| Boundary::Dead.offset(x, y, dx, dy, width, height))
+                .filter(|&(nx, ny)| grid.get(nx, ny))
+                .count();
+            next.set(x, y, Rule::CONWAY.next_state(alive, neighbors as i32));
+        }
+        next
+    }
+
+    /// A random board where only about a quarter of the rows hold any live
+    /// cells, so plenty of rows are quiet.
+    fn sparse_board(rng: &mut Rng, width: usize, height: usize) -> BoolGrid2D {
+        let mut grid = BoolGrid2D::new(width, height);
+        for y in 0..height {
+            if rng.next_f32() < 0.25 {
+                for x in 0..width {
+                    grid.set(x, y, rng.next_f32() < 0.4);
+                }
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn step_matches_the_naive_rule_on_sparse_boards() {
+        let mut rng = Rng::new(105);
+        for _ in 0..300 {
+            let width = 1 + (rng.next_u64() % 150) as usize;
+            let height = 1 + (rng.next_u64() % 40) as usize;
+            let grid = sparse_board(&mut rng, width, height);
+
+            let mut stepped = BoolGrid2D::new(width, height);
+            PackedGrid::from_grid(&grid).step().write_to(&mut stepped);
+            assert_eq!(
+                stepped,
+                naive_step(&grid),
+                "{}x{} board:\n{}",
+                width,
+                height,
+                grid.to_plaintext()
+            );
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufWriter, Write},
+    time::{Duration, Instant},
+};
+
+use conways_gol::game::Game;
+
+/// How often buffered rows are written out, so a crash loses little.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Appends a `generation,population,births,deaths` CSV row for every
+/// generation, buffering them between flushes.
+pub struct PopulationLog {
+    path: String,
+    writer: BufWriter<File>,
+    last_flushed: Instant,
+    /// `Game::total_births` and `total_deaths` at the last row, so each row
+    /// gets just its own generation's changes.
+    births: u64,
+    deaths: u64,
+}
+
+impl PopulationLog {
+    /// Opens `path` for appending, starting it with a header row if it's
+    /// new or empty.
+    pub fn open(path: &str, game: &Game) -> io::Result<PopulationLog> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let empty = file.metadata()?.len() == 0;
+        let mut writer = BufWriter::new(file);
+        if empty {
+            writeln!(writer, "generation,population,births,deaths")?;
+        }
+
+        Ok(PopulationLog {
+            path: path.to_string(),
+            writer,
+            last_flushed: Instant::now(),
+            births: game.total_births,
+            deaths: game.total_deaths,
+        })
+    }
+
+    /// Adds the row for the generation `game` just reached.
+    pub fn record(&mut self, game: &Game) {
+        // The totals start over when the board is cleared.
+        let since = |total: u64, last: u64| total.checked_sub(last).unwrap_or(total);
+        let births = since(game.total_births, self.births);
+        let deaths = since(game.total_deaths, self.deaths);
+        (self.births, self.deaths) = (game.total_births, game.total_deaths);
+
+        let written = writeln!(
+            self.writer,
+            "{},{},{},{}",
+            game.generation,
+            game.grid.population(),
+            births,
+            deaths
+        );
+        if let Err(error) = written {
+            eprintln!("Couldn't log the population to {}: {}", self.path, error);
+        }
+
+        if self.last_flushed.elapsed() >= FLUSH_INTERVAL {
+            self.flush();
+        }
+    }
+
+    /// Writes out every buffered row. Call it before exiting, since
+    /// `process::exit` skips the flush dropping would do.
+    pub fn flush(&mut self) {
+        self.last_flushed = Instant::now();
+        if let Err(error) = self.writer.flush() {
+            eprintln!("Couldn't log the population to {}: {}", self.path, error);
+        }
+    }
+}
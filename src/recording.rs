@@ -0,0 +1,85 @@
+use std::{borrow::Cow, fs::File, io::BufWriter};
+
+use conways_gol::game::Game;
+use gif::{Encoder, Frame, Repeat};
+use sfml::graphics::Color;
+
+/// Palette indices of the two colors every frame is drawn with.
+const DEAD: u8 = 0;
+const ALIVE: u8 = 1;
+
+/// One pixel per byte, each a palette index, with every cell drawn as a
+/// `cell_width` by `cell_height` block.
+fn render(game: &Game, cell_width: usize, cell_height: usize) -> Vec<u8> {
+    let grid = &game.grid;
+    let frame_width = grid.width() * cell_width;
+    let mut pixels = vec![DEAD; frame_width * grid.height() * cell_height];
+
+    for (x, y) in grid.live_cells() {
+        for row in y * cell_height..(y + 1) * cell_height {
+            let start = row * frame_width + x * cell_width;
+            pixels[start..start + cell_width].fill(ALIVE);
+        }
+    }
+
+    pixels
+}
+
+/// Runs `generations` generations and writes each one as a frame of an
+/// animated GIF at `path`, `delay_ms` apart. Frames are encoded as they're
+/// simulated, so long runs don't pile up in memory.
+pub fn record(game: &mut Game, path: &str, generations: u64, delay_ms: u64) -> Result<(), String> {
+    let cell_width = game.cell_size.x.round().max(1.0) as usize;
+    let cell_height = game.cell_size.y.round().max(1.0) as usize;
+    let too_large = || {
+        format!(
+            "a {}x{} board at {}x{} pixels per cell is too large for a GIF",
+            game.grid.width(),
+            game.grid.height(),
+            cell_width,
+            cell_height
+        )
+    };
+    let width = u16::try_from(game.grid.width() * cell_width).map_err(|_| too_large())?;
+    let height = u16::try_from(game.grid.height() * cell_height).map_err(|_| too_large())?;
+
+    let [dead, alive] = [
+        game.dead_cell_color.unwrap_or(Color::BLACK),
+        game.cell_color,
+    ]
+    .map(|color| [color.red(), color.green(), color.blue()]);
+    let palette = [dead, alive].concat();
+
+    let file =
+        File::create(path).map_err(|error| format!("couldn't create {}: {}", path, error))?;
+    let mut encoder = Encoder::new(BufWriter::new(file), width, height, &palette)
+        .map_err(|error| error.to_string())?;
+    encoder
+        .set_repeat(Repeat::Infinite)
+        .map_err(|error| error.to_string())?;
+
+    // GIF delays are in hundredths of a second.
+    let delay = u16::try_from(delay_ms / 10).unwrap_or(u16::MAX);
+
+    for generation in 0..=generations {
+        if generation > 0 {
+            game.update();
+        }
+
+        let frame = Frame {
+            width,
+            height,
+            delay,
+            buffer: Cow::Owned(render(game, cell_width, cell_height)),
+            ..Frame::default()
+        };
+        encoder
+            .write_frame(&frame)
+            .map_err(|error| error.to_string())?;
+    }
+
+    encoder
+        .into_inner()
+        .map(drop)
+        .map_err(|error| error.to_string())
+}
@@ -0,0 +1,109 @@
+use std::time::{Duration, Instant};
+
+use conways_gol::{game::Game, rng::Rng};
+use sfml::system::Vector2;
+
+/// The order `Reveal` brings cells in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum RevealOrder {
+    /// Row by row from the top-left.
+    #[default]
+    Scan,
+    /// Shuffled by the game's seed, so a replayed session reveals alike.
+    Random,
+}
+
+/// An intro that starts from an empty board and brings the starting live
+/// cells in a few at a time over `duration`, holding the simulation paused
+/// until they're all back.
+pub struct Reveal {
+    /// Cells still to bring back, in reverse order so the next is last.
+    pending: Vec<Vector2<usize>>,
+    total: usize,
+    started: Instant,
+    duration: Duration,
+    /// Whether each board was paused before the reveal took over.
+    was_paused: (bool, bool),
+}
+
+impl Reveal {
+    /// Empties `game`, and `comparison` along with it since it starts from
+    /// the same cells, and pauses both until the reveal ends.
+    pub fn start(
+        game: &mut Game,
+        comparison: &mut Option<Game>,
+        duration: Duration,
+        order: RevealOrder,
+    ) -> Reveal {
+        let mut pending: Vec<Vector2<usize>> = game
+            .grid
+            .live_cells()
+            .map(|(x, y)| Vector2::new(x, y))
+            .collect();
+        if order == RevealOrder::Random {
+            // A generator of its own leaves the game's random sequence alone.
+            let mut rng = Rng::new(game.seed);
+            for i in (1..pending.len()).rev() {
+                let j = (rng.next_u64() % (i as u64 + 1)) as usize;
+                pending.swap(i, j);
+            }
+        }
+        pending.reverse();
+
+        let was_paused = (
+            game.paused,
+            comparison.as_ref().is_some_and(|other| other.paused),
+        );
+        for board in std::iter::once(&mut *game).chain(comparison.as_mut()) {
+            for &cell in &pending {
+                board.set_cell(cell, false);
+            }
+            board.paused = true;
+        }
+
+        Reveal {
+            total: pending.len(),
+            pending,
+            started: Instant::now(),
+            duration,
+            was_paused,
+        }
+    }
+
+    /// Brings back as many cells as the time elapsed calls for. Returns
+    /// whether the reveal is still going; once it isn't, the boards are
+    /// back to how they'd have started without it.
+    pub fn tick(&mut self, game: &mut Game, comparison: &mut Option<Game>) -> bool {
+        let progress = if self.duration.is_zero() {
+            1.0
+        } else {
+            (self.started.elapsed().as_secs_f64() / self.duration.as_secs_f64()).min(1.0)
+        };
+        let due = (self.total as f64 * progress).ceil() as usize;
+        let revealed = self.total - self.pending.len();
+
+        for _ in revealed..due {
+            let Some(cell) = self.pending.pop() else {
+                break;
+            };
+            for board in std::iter::once(&mut *game).chain(comparison.as_mut()) {
+                board.set_cell(cell, true);
+            }
+        }
+
+        if !self.pending.is_empty() {
+            return true;
+        }
+        game.paused = self.was_paused.0;
+        if let Some(other) = comparison {
+            other.paused = self.was_paused.1;
+        }
+        false
+    }
+
+    /// Brings back every remaining cell at once and ends the reveal.
+    pub fn skip(&mut self, game: &mut Game, comparison: &mut Option<Game>) {
+        self.duration = Duration::ZERO;
+        self.tick(game, comparison);
+    }
+}
@@ -0,0 +1,241 @@
+use std::fmt;
+
+use crate::{game::MAX_BOARD_CELLS, grid::BoolGrid2D, rule::Rule};
+
+/// Why a piece of RLE text couldn't be read.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum RleError {
+    MissingHeader,
+    InvalidHeader(String),
+    UnexpectedCharacter(char),
+    /// A row or column ran past the size given in the header.
+    OutOfBounds,
+    /// The header asks for a board of more than `MAX_BOARD_CELLS` cells.
+    TooLarge {
+        width: usize,
+        height: usize,
+    },
+}
+
+impl fmt::Display for RleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RleError::MissingHeader => write!(f, "missing `x = .., y = ..` header"),
+            RleError::InvalidHeader(header) => write!(f, "invalid header {:?}", header),
+            RleError::UnexpectedCharacter(c) => write!(f, "unexpected character {:?}", c),
+            RleError::OutOfBounds => write!(f, "pattern is larger than its header says"),
+            RleError::TooLarge { width, height } => write!(
+                f,
+                "{}x{} is more than the {} cells a board can hold",
+                width, height, MAX_BOARD_CELLS
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RleError {}
+
+/// Reads `x = 3, y = 1, rule = B3/S23` style headers into the size and,
+/// if there is one, the rule. Other fields, and rules in notations `Rule`
+/// can't read, are ignored so the cells still load.
+fn parse_header(line: &str) -> Result<(usize, usize, Option<Rule>), RleError> {
+    let invalid = || RleError::InvalidHeader(line.to_string());
+    let mut width = None;
+    let mut height = None;
+    let mut rule = None;
+
+    for field in line.split(',') {
+        let (key, value) = field.split_once('=').ok_or_else(invalid)?;
+        match key.trim() {
+            "x" => width = Some(value.trim().parse().map_err(|_| invalid())?),
+            "y" => height = Some(value.trim().parse().map_err(|_| invalid())?),
+            "rule" => rule = value.parse().ok(),
+            _ => (),
+        }
+    }
+
+    Ok((
+        width.ok_or_else(invalid)?,
+        height.ok_or_else(invalid)?,
+        rule,
+    ))
+}
+
+/// Parses a pattern in the run-length encoded format used by most Life
+/// software. The result is exactly as large as the header declares.
+pub fn from_rle(text: &str) -> Result<BoolGrid2D, RleError> {
+    from_rle_with_rule(text).map(|(pattern, _)| pattern)
+}
+
+/// Like `from_rle`, but also returns the rule the header names, if any.
+pub fn from_rle_with_rule(text: &str) -> Result<(BoolGrid2D, Option<Rule>), RleError> {
+    let mut lines = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+    let (width, height, rule) = parse_header(lines.next().ok_or(RleError::MissingHeader)?)?;
+    if width
+        .checked_mul(height)
+        .is_none_or(|cells| cells > MAX_BOARD_CELLS)
+    {
+        return Err(RleError::TooLarge { width, height });
+    }
+    let mut pattern = BoolGrid2D::new(width, height);
+
+    let (mut x, mut y) = (0usize, 0usize);
+    let mut run = 0usize;
+
+    'body: for line in lines {
+        for c in line.chars() {
+            match c {
+                // A run too long to count can't fit the board either.
+                '0'..='9' => {
+                    run = run
+                        .checked_mul(10)
+                        .and_then(|run| run.checked_add(c.to_digit(10).unwrap_or(0) as usize))
+                        .ok_or(RleError::OutOfBounds)?
+                }
+                '!' => break 'body,
+                '$' => {
+                    y = y.checked_add(run.max(1)).ok_or(RleError::OutOfBounds)?;
+                    x = 0;
+                    run = 0;
+                }
+                c if c.is_ascii_alphabetic() => {
+                    let count = run.max(1);
+                    let end = x.checked_add(count).ok_or(RleError::OutOfBounds)?;
+                    // Anything but `b` is a live state; multi-state letters
+                    // are treated as plain live cells.
+                    if c != 'b' {
+                        if end > width || y >= height {
+                            return Err(RleError::OutOfBounds);
+                        }
+                        for dx in x..end {
+                            pattern.set(dx, y, true);
+                        }
+                    }
+                    x = end;
+                    run = 0;
+                }
+                c if c.is_whitespace() => (),
+                c => return Err(RleError::UnexpectedCharacter(c)),
+            }
+        }
+    }
+
+    Ok((pattern, rule))
+}
+
+/// Where the pattern's top-left cell belongs, from a `#P x y` or `#R x y`
+/// line, if the text has one.
+pub fn position(text: &str) -> Option<(isize, isize)> {
+    text.lines().find_map(|line| {
+        let rest = line
+            .trim()
+            .strip_prefix("#P")
+            .or_else(|| line.trim().strip_prefix("#R"))?;
+        let mut numbers = rest.split_whitespace().map(str::parse);
+        match (numbers.next(), numbers.next()) {
+            (Some(Ok(x)), Some(Ok(y))) => Some((x, y)),
+            _ => None,
+        }
+    })
+}
+
+/// Lines of pattern data are wrapped to stay under this length, as the
+/// format asks.
+const MAX_LINE_LENGTH: usize = 70;
+
+/// One run of `count` copies of `tag`, leaving out a count of one.
+fn run(count: usize, tag: char) -> String {
+    if count > 1 {
+        format!("{}{}", count, tag)
+    } else {
+        tag.to_string()
+    }
+}
+
+/// Writes `grid` in the format `from_rle` reads, at its full size, with
+/// `rule` in the header.
+pub fn to_rle(grid: &BoolGrid2D, rule: Rule) -> String {
+    let mut items = Vec::new();
+    let mut pending_rows = 0;
+
+    for row in grid.array.chunks(grid.width) {
+        // Trailing dead cells are implied by the next `$`.
+        let Some(last_alive) = row.iter().rposition(|&alive| alive) else {
+            pending_rows += 1;
+            continue;
+        };
+
+        let row_breaks = pending_rows + usize::from(!items.is_empty());
+        if row_breaks > 0 {
+            items.push(run(row_breaks, '$'));
+        }
+        pending_rows = 0;
+
+        let mut cells = row[..=last_alive].iter().peekable();
+        while let Some(&alive) = cells.next() {
+            let mut count = 1;
+            while cells.next_if(|&&next| next == alive).is_some() {
+                count += 1;
+            }
+            items.push(run(count, if alive { 'o' } else { 'b' }));
+        }
+    }
+    items.push("!".to_string());
+
+    let mut text = format!("x = {}, y = {}, rule = {}\n", grid.width, grid.height, rule);
+    let mut line_length = 0;
+    for item in items {
+        if line_length + item.len() > MAX_LINE_LENGTH {
+            text.push('\n');
+            line_length = 0;
+        }
+        line_length += item.len();
+        text.push_str(&item);
+    }
+    text.push('\n');
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_too_long_to_count_are_out_of_bounds() {
+        let digits = "9".repeat(40);
+        for body in [
+            format!("{}o!", digits),
+            format!("{}$o!", digits),
+            format!("{}b{}bo!", usize::MAX, usize::MAX),
+            format!("{}$${}$o!", usize::MAX, usize::MAX),
+        ] {
+            assert_eq!(
+                from_rle(&format!("x = 3, y = 3\n{}", body)),
+                Err(RleError::OutOfBounds),
+                "{}",
+                body
+            );
+        }
+    }
+
+    #[test]
+    fn headers_larger_than_a_board_are_rejected() {
+        for (width, height) in [(usize::MAX, 2), (1 << 20, 1 << 20)] {
+            assert_eq!(
+                from_rle(&format!("x = {}, y = {}\no!", width, height)),
+                Err(RleError::TooLarge { width, height })
+            );
+        }
+    }
+
+    #[test]
+    fn runs_still_fill_the_board() {
+        let pattern = from_rle("x = 4, y = 3\n2bo$$4o!").unwrap();
+        let cells: Vec<_> = pattern.live_cells().collect();
+        assert_eq!(cells, [(2, 0), (0, 2), (1, 2), (2, 2), (3, 2)]);
+    }
+}
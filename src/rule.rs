@@ -0,0 +1,160 @@
+use std::{fmt, str::FromStr};
+
+/// A life-like rule: which neighbor counts bring a dead cell to life and
+/// which keep a live cell alive.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Rule {
+    pub birth: [bool; 9],
+    pub survival: [bool; 9],
+}
+
+impl Rule {
+    /// Conway's Game of Life, B3/S23.
+    pub const CONWAY: Rule = Rule {
+        birth: [false, false, false, true, false, false, false, false, false],
+        survival: [false, false, true, true, false, false, false, false, false],
+    };
+
+    /// The best-known Life-like rule for hexagonal grids, B2/S34, where
+    /// each cell has only 6 neighbors.
+    pub const HEX_LIFE: Rule = Rule {
+        birth: [false, false, true, false, false, false, false, false, false],
+        survival: [false, false, false, true, true, false, false, false, false],
+    };
+
+    pub fn next_state(&self, is_alive: bool, neighbors_count: i32) -> bool {
+        let counts = if is_alive {
+            &self.survival
+        } else {
+            &self.birth
+        };
+        usize::try_from(neighbors_count)
+            .ok()
+            .and_then(|count| counts.get(count))
+            .copied()
+            .unwrap_or(false)
+    }
+
+    /// The rule with one neighbor count flipped: `0..9` pick a birth count
+    /// and `9..18` a survival count. Panics past 17.
+    pub fn with_flipped(&self, index: usize) -> Rule {
+        let mut rule = *self;
+        let counts = if index < 9 {
+            &mut rule.birth
+        } else {
+            &mut rule.survival
+        };
+        counts[index % 9] = !counts[index % 9];
+        rule
+    }
+
+    /// The common name of a well-known rule.
+    pub fn name(&self) -> Option<&'static str> {
+        NAMED_RULES
+            .iter()
+            .find(|(_, rule)| rule == &self.to_string())
+            .map(|&(name, _)| name)
+    }
+
+    /// The rule spelled out, like "Birth on 3; survival on 2, 3 (Conway's
+    /// Life)". Rules without a name are followed by their B/S string instead.
+    pub fn describe(&self) -> String {
+        let counts = |counts: &[bool; 9]| -> String {
+            let listed: Vec<String> = (0..9)
+                .filter(|&count| counts[count])
+                .map(|count| count.to_string())
+                .collect();
+            if listed.is_empty() {
+                "none".to_string()
+            } else {
+                listed.join(", ")
+            }
+        };
+        let name = self.name().map_or_else(|| self.to_string(), str::to_string);
+
+        format!(
+            "Birth on {}; survival on {} ({})",
+            counts(&self.birth),
+            counts(&self.survival),
+            name
+        )
+    }
+}
+
+/// Rules well-known enough to go by name, in `B3/S23` notation.
+const NAMED_RULES: &[(&str, &str)] = &[
+    ("Conway's Life", "B3/S23"),
+    ("HighLife", "B36/S23"),
+    ("Seeds", "B2/S"),
+    ("Day & Night", "B3678/S34678"),
+    ("Replicator", "B1357/S1357"),
+];
+
+impl Default for Rule {
+    fn default() -> Rule {
+        Rule::CONWAY
+    }
+}
+
+/// Writes the rule in `B3/S23` notation.
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = |counts: &[bool; 9]| -> String {
+            (0..9)
+                .filter(|&count| counts[count])
+                .map(|count| char::from(b'0' + count as u8))
+                .collect()
+        };
+        write!(f, "B{}/S{}", digits(&self.birth), digits(&self.survival))
+    }
+}
+
+/// A rule string that isn't in `B3/S23` notation.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct InvalidRule(pub String);
+
+impl fmt::Display for InvalidRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid rule {:?}, expected B/S notation like B3/S23",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for InvalidRule {}
+
+/// Reads `B3/S23` notation, in either case.
+impl FromStr for Rule {
+    type Err = InvalidRule;
+
+    fn from_str(text: &str) -> Result<Rule, InvalidRule> {
+        let invalid = || InvalidRule(text.to_string());
+        let (birth, survival) = text.trim().split_once('/').ok_or_else(invalid)?;
+
+        let counts = |part: &str, prefix: char| -> Result<[bool; 9], InvalidRule> {
+            let mut chars = part.chars();
+            if !chars
+                .next()
+                .is_some_and(|c| c.eq_ignore_ascii_case(&prefix))
+            {
+                return Err(invalid());
+            }
+
+            let mut counts = [false; 9];
+            for c in chars {
+                match c.to_digit(10) {
+                    Some(count) if count < 9 => counts[count as usize] = true,
+                    _ => return Err(invalid()),
+                }
+            }
+            Ok(counts)
+        };
+
+        Ok(Rule {
+            birth: counts(birth, 'B')?,
+            survival: counts(survival, 'S')?,
+        })
+    }
+}
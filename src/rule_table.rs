@@ -0,0 +1,270 @@
+use std::{fmt, str::FromStr};
+
+use crate::rule::Rule;
+
+/// A totalistic rule written out in full: for every state a cell can be in
+/// and every count of neighbors in a non-zero state, the state it moves to.
+///
+/// Tables are read from text like this, which is Conway's rule:
+///
+/// ```text
+/// states = 2
+/// # state  neighbor counts  -> next state
+/// 0 0-2,4-8 -> 0
+/// 0 3       -> 1
+/// 1 2,3     -> 1
+/// 1 0,1,4-8 -> 0
+/// ```
+///
+/// Every state and count must be covered exactly once.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RuleTable {
+    /// `next[state][count]`.
+    next: Vec<[u8; 9]>,
+}
+
+impl RuleTable {
+    pub fn states(&self) -> usize {
+        self.next.len()
+    }
+
+    pub fn next_state(&self, state: u8, neighbors_count: usize) -> u8 {
+        self.next[state as usize][neighbors_count]
+    }
+}
+
+/// Why a rule table couldn't be read. Line numbers start at 1.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum RuleTableError {
+    MissingStates,
+    InvalidLine(usize, String),
+    StateOutOfRange(usize, u8),
+    Duplicate {
+        state: u8,
+        count: usize,
+        line: usize,
+    },
+    Missing {
+        state: u8,
+        count: usize,
+    },
+    /// The table is fine, but has more states than a two-state `Rule` can
+    /// stand for. Run it with `Game::set_rule_table` instead.
+    TooManyStates(usize),
+}
+
+impl fmt::Display for RuleTableError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RuleTableError::MissingStates => {
+                write!(f, "missing `states = N` line before the table")
+            }
+            RuleTableError::InvalidLine(line, text) => {
+                write!(
+                    f,
+                    "line {}: expected `STATE COUNTS -> NEXT`, got {:?}",
+                    line, text
+                )
+            }
+            RuleTableError::StateOutOfRange(line, state) => {
+                write!(f, "line {}: there is no state {}", line, state)
+            }
+            RuleTableError::Duplicate { state, count, line } => write!(
+                f,
+                "line {}: state {} with {} neighbors is already covered",
+                line, state, count
+            ),
+            RuleTableError::Missing { state, count } => write!(
+                f,
+                "nothing says what state {} with {} neighbors becomes",
+                state, count
+            ),
+            RuleTableError::TooManyStates(states) => write!(
+                f,
+                "the table has {} states, but a life-like rule only has 2",
+                states
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RuleTableError {}
+
+/// Reads a list like `0-2,4,6-8` into the neighbor counts it names.
+fn parse_counts(text: &str) -> Option<Vec<usize>> {
+    let mut counts = Vec::new();
+    for part in text.split(',') {
+        let (low, high) = part.split_once('-').unwrap_or((part, part));
+        let (low, high): (usize, usize) = (low.trim().parse().ok()?, high.trim().parse().ok()?);
+        if low > high || high > 8 {
+            return None;
+        }
+        counts.extend(low..=high);
+    }
+    Some(counts)
+}
+
+impl FromStr for RuleTable {
+    type Err = RuleTableError;
+
+    fn from_str(text: &str) -> Result<RuleTable, RuleTableError> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.split('#').next().unwrap_or("").trim()))
+            .filter(|(_, line)| !line.is_empty());
+
+        let states = lines
+            .next()
+            .and_then(|(_, line)| {
+                let (key, value) = line.split_once('=')?;
+                (key.trim() == "states").then_some(())?;
+                value.trim().parse::<u8>().ok()
+            })
+            .filter(|&states| states >= 2)
+            .ok_or(RuleTableError::MissingStates)?;
+
+        let mut next = vec![[None; 9]; states as usize];
+        for (number, line) in lines {
+            let invalid = || RuleTableError::InvalidLine(number, line.to_string());
+            let (left, result) = line.split_once("->").ok_or_else(invalid)?;
+            let (state, counts) = left
+                .trim()
+                .split_once(char::is_whitespace)
+                .ok_or_else(invalid)?;
+            let state: u8 = state.parse().map_err(|_| invalid())?;
+            let result: u8 = result.trim().parse().map_err(|_| invalid())?;
+            let counts = parse_counts(counts).ok_or_else(invalid)?;
+
+            for checked in [state, result] {
+                if checked >= states {
+                    return Err(RuleTableError::StateOutOfRange(number, checked));
+                }
+            }
+            for count in counts {
+                let entry = &mut next[state as usize][count];
+                if entry.is_some() {
+                    return Err(RuleTableError::Duplicate {
+                        state,
+                        count,
+                        line: number,
+                    });
+                }
+                *entry = Some(result);
+            }
+        }
+
+        let mut table = RuleTable {
+            next: vec![[0; 9]; states as usize],
+        };
+        for (state, row) in next.iter().enumerate() {
+            for (count, entry) in row.iter().enumerate() {
+                table.next[state][count] = entry.ok_or(RuleTableError::Missing {
+                    state: state as u8,
+                    count,
+                })?;
+            }
+        }
+        Ok(table)
+    }
+}
+
+/// Two-state tables are just life-like rules, and run as one.
+impl TryFrom<&RuleTable> for Rule {
+    type Error = RuleTableError;
+
+    fn try_from(table: &RuleTable) -> Result<Rule, RuleTableError> {
+        if table.states() != 2 {
+            return Err(RuleTableError::TooManyStates(table.states()));
+        }
+
+        let mut rule = Rule {
+            birth: [false; 9],
+            survival: [false; 9],
+        };
+        for count in 0..9 {
+            rule.birth[count] = table.next_state(0, count) == 1;
+            rule.survival[count] = table.next_state(1, count) == 1;
+        }
+        Ok(rule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONWAY: &str = "states = 2
+0 0-2,4-8 -> 0
+0 3       -> 1
+1 2,3     -> 1
+1 0,1,4-8 -> 0
+";
+
+    #[test]
+    fn conway_table_is_the_conway_rule() {
+        let table: RuleTable = CONWAY.parse().unwrap();
+        assert_eq!(Rule::try_from(&table), Ok(Rule::CONWAY));
+    }
+
+    #[test]
+    fn tables_must_cover_every_state_and_count() {
+        let without_birth = CONWAY.replace("0 3       -> 1\n", "");
+        assert_eq!(
+            without_birth.parse::<RuleTable>(),
+            Err(RuleTableError::Missing { state: 0, count: 3 })
+        );
+
+        let with_three_states = CONWAY.replace("states = 2", "states = 3");
+        assert_eq!(
+            with_three_states.parse::<RuleTable>(),
+            Err(RuleTableError::Missing { state: 2, count: 0 })
+        );
+    }
+
+    #[test]
+    fn tables_may_cover_each_pair_only_once() {
+        let doubled = format!("{}0 2-3 -> 1\n", CONWAY);
+        assert_eq!(
+            doubled.parse::<RuleTable>(),
+            Err(RuleTableError::Duplicate {
+                state: 0,
+                count: 2,
+                line: 6
+            })
+        );
+    }
+
+    #[test]
+    fn tables_are_checked_line_by_line() {
+        assert_eq!(
+            "0 3 -> 1".parse::<RuleTable>(),
+            Err(RuleTableError::MissingStates)
+        );
+        assert_eq!(
+            "states = 2\n0 3 -> 2".parse::<RuleTable>(),
+            Err(RuleTableError::StateOutOfRange(2, 2))
+        );
+        assert_eq!(
+            "states = 2\n0 9 -> 1".parse::<RuleTable>(),
+            Err(RuleTableError::InvalidLine(2, "0 9 -> 1".to_string()))
+        );
+    }
+
+    #[test]
+    fn tables_with_more_states_are_not_rules() {
+        let table: RuleTable = "states = 3
+0 2       -> 1
+0 0,1,3-8 -> 0
+1 0-8     -> 2
+2 0-8     -> 0
+"
+        .parse()
+        .unwrap();
+        assert_eq!(table.next_state(1, 4), 2);
+        assert_eq!(
+            Rule::try_from(&table),
+            Err(RuleTableError::TooManyStates(3))
+        );
+    }
+}
@@ -0,0 +1,271 @@
+use sfml::system::{Clock, Time};
+
+/// Anything that measures elapsed time. The main loop runs on SFML's
+/// `Clock`; `ManualClock` stands in where time should only move when told
+/// to.
+pub trait TimeSource {
+    /// Time since the source started or was last restarted.
+    fn elapsed_time(&self) -> Time;
+    /// Starts counting from zero again, returning the time elapsed before.
+    fn restart(&mut self) -> Time;
+}
+
+impl TimeSource for Clock {
+    fn elapsed_time(&self) -> Time {
+        Clock::elapsed_time(self)
+    }
+
+    fn restart(&mut self) -> Time {
+        Clock::restart(self)
+    }
+}
+
+/// A clock that only moves when `advance` is called.
+#[derive(Clone, Copy, Debug)]
+pub struct ManualClock {
+    elapsed: Time,
+}
+
+impl ManualClock {
+    pub fn new() -> ManualClock {
+        ManualClock {
+            elapsed: Time::ZERO,
+        }
+    }
+
+    pub fn advance(&mut self, by: Time) {
+        self.elapsed += by;
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> ManualClock {
+        ManualClock::new()
+    }
+}
+
+impl TimeSource for ManualClock {
+    fn elapsed_time(&self) -> Time {
+        self.elapsed
+    }
+
+    fn restart(&mut self) -> Time {
+        std::mem::replace(&mut self.elapsed, Time::ZERO)
+    }
+}
+
+/// What one frame's worth of ticking did.
+#[derive(Clone, Copy, Debug)]
+pub struct Ticks {
+    pub run: u32,
+    /// Total time the steps reported spending in the simulation itself.
+    pub step_time: Time,
+    /// Whether ticks were still due when the budget ran out.
+    pub out_of_budget: bool,
+}
+
+/// Decides how many generations each frame runs so the simulation keeps
+/// pace with real time, without letting a heavy board take over the frame.
+#[derive(Clone, Copy, Debug)]
+pub struct TickScheduler {
+    /// Time since the last tick that hasn't been simulated yet.
+    owed: Time,
+    /// How long a frame may spend catching up.
+    budget: Time,
+}
+
+impl TickScheduler {
+    pub fn new(budget: Time) -> TickScheduler {
+        TickScheduler {
+            owed: Time::ZERO,
+            budget,
+        }
+    }
+
+    /// Records real time that passed, such as a frame's `restart`.
+    pub fn add_elapsed(&mut self, elapsed: Time) {
+        self.owed += elapsed;
+    }
+
+    /// Calls `step` for every `tick_duration` owed, giving up once
+    /// `frame_clock` says the budget is spent. A backlog left over then is
+    /// dropped rather than replayed later. `step` returns how long the
+    /// simulation took.
+    pub fn catch_up(
+        &mut self,
+        tick_duration: Time,
+        frame_clock: &impl TimeSource,
+        mut step: impl FnMut() -> Time,
+    ) -> Ticks {
+        let mut ticks = Ticks {
+            run: 0,
+            step_time: Time::ZERO,
+            out_of_budget: false,
+        };
+
+        while self.owed > tick_duration {
+            if frame_clock.elapsed_time() > self.budget {
+                ticks.out_of_budget = true;
+                self.owed = tick_duration;
+                break;
+            }
+
+            self.owed -= tick_duration;
+            ticks.step_time += step();
+            ticks.run += 1;
+        }
+
+        ticks
+    }
+
+    /// Calls `step` exactly once, however much time is owed, and forgets
+    /// the rest. Max speed uses this so generations match frames drawn.
+    pub fn step_once(&mut self, step: impl FnOnce() -> Time) -> Ticks {
+        self.owed = Time::ZERO;
+        Ticks {
+            run: 1,
+            step_time: step(),
+            out_of_budget: false,
+        }
+    }
+
+    /// Calls `step` `count` times whatever the clock says, with no budget,
+    /// so every frame moves the same number of generations.
+    pub fn step_exactly(&mut self, count: u32, mut step: impl FnMut() -> Time) -> Ticks {
+        self.owed = Time::ZERO;
+        let mut ticks = Ticks {
+            run: count,
+            step_time: Time::ZERO,
+            out_of_budget: false,
+        };
+        for _ in 0..count {
+            ticks.step_time += step();
+        }
+        ticks
+    }
+
+    /// One frame's ticking: `ticks_per_frame` steps if that's set, one step
+    /// at max speed, and otherwise as many as `catch_up` finds owed.
+    pub fn run_frame(
+        &mut self,
+        ticks_per_frame: Option<u32>,
+        max_speed: bool,
+        tick_duration: Time,
+        frame_clock: &impl TimeSource,
+        step: impl FnMut() -> Time,
+    ) -> Ticks {
+        if let Some(count) = ticks_per_frame {
+            self.step_exactly(count, step)
+        } else if max_speed {
+            self.step_once(step)
+        } else {
+            self.catch_up(tick_duration, frame_clock, step)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+
+    /// A step that takes `took` and counts how often it ran.
+    fn counting_step(runs: &mut u32, took: Time) -> impl FnMut() -> Time + '_ {
+        move || {
+            *runs += 1;
+            took
+        }
+    }
+
+    #[test]
+    fn catch_up_runs_every_tick_owed() {
+        let mut scheduler = TickScheduler::new(Time::milliseconds(10));
+        let frame_clock = ManualClock::new();
+        let mut runs = 0;
+
+        scheduler.add_elapsed(Time::milliseconds(35));
+        let ticks = scheduler.catch_up(
+            Time::milliseconds(10),
+            &frame_clock,
+            counting_step(&mut runs, Time::milliseconds(1)),
+        );
+        assert_eq!((ticks.run, runs), (3, 3));
+        assert_eq!(ticks.step_time, Time::milliseconds(3));
+        assert!(!ticks.out_of_budget);
+
+        // The 5ms left over carries into the next frame.
+        scheduler.add_elapsed(Time::milliseconds(10));
+        let ticks = scheduler.catch_up(Time::milliseconds(10), &frame_clock, || Time::ZERO);
+        assert_eq!(ticks.run, 1);
+    }
+
+    #[test]
+    fn catch_up_drops_the_backlog_once_the_budget_is_spent() {
+        let mut scheduler = TickScheduler::new(Time::milliseconds(10));
+        let mut frame_clock = ManualClock::new();
+        frame_clock.advance(Time::milliseconds(11));
+
+        scheduler.add_elapsed(Time::seconds(1.0));
+        let ticks = scheduler.catch_up(Time::milliseconds(10), &frame_clock, || Time::ZERO);
+        assert_eq!(ticks.run, 0);
+        assert!(ticks.out_of_budget);
+
+        // Only one tick's worth is kept, so the next frame runs none until
+        // more time passes.
+        let frame_clock = ManualClock::new();
+        let ticks = scheduler.catch_up(Time::milliseconds(10), &frame_clock, || Time::ZERO);
+        assert_eq!(ticks.run, 0);
+        assert!(!ticks.out_of_budget);
+    }
+
+    #[test]
+    fn step_exactly_ignores_the_clock_and_the_budget() {
+        let mut scheduler = TickScheduler::new(Time::milliseconds(10));
+        let mut frame_clock = ManualClock::new();
+        frame_clock.advance(Time::seconds(1.0));
+        let mut runs = 0;
+
+        scheduler.add_elapsed(Time::seconds(1.0));
+        let ticks = scheduler.run_frame(
+            Some(4),
+            false,
+            Time::milliseconds(10),
+            &frame_clock,
+            counting_step(&mut runs, Time::milliseconds(2)),
+        );
+        assert_eq!((ticks.run, runs), (4, 4));
+        assert_eq!(ticks.step_time, Time::milliseconds(8));
+        assert!(!ticks.out_of_budget);
+
+        // Nothing owed is left for a later catch-up to replay.
+        let ticks = scheduler.catch_up(Time::milliseconds(10), &ManualClock::new(), || Time::ZERO);
+        assert_eq!(ticks.run, 0);
+    }
+
+    #[test]
+    fn frames_at_max_speed_run_one_generation_each() {
+        let mut game = Game::new(20, 20);
+        game.max_speed = true;
+        game.tick_duration = Time::milliseconds(1);
+        let mut scheduler = TickScheduler::new(Time::milliseconds(10));
+        let frame_clock = ManualClock::new();
+
+        for frame in 1..=50 {
+            // However much time a frame owes, max speed runs exactly one
+            // generation.
+            scheduler.add_elapsed(Time::milliseconds(frame * 7));
+            let ticks = scheduler.run_frame(
+                None,
+                game.max_speed,
+                game.tick_duration,
+                &frame_clock,
+                || {
+                    game.update();
+                    game.step_time
+                },
+            );
+            assert_eq!(ticks.run, 1);
+        }
+        assert_eq!(game.generation, 50);
+    }
+}